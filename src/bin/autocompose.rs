@@ -8,17 +8,26 @@ Commercial use, redistribution, or reuse in other software is prohibited without
 Contact: contact@olympus-chain.fr
 */
 
+use bollard::models::ContainerSummary;
 use clap::Parser;
 use autocompose::{
-    cli::{get_config_path, load_config, save_config, AppConfig, Cli, Commands, ConfigAction},
+    cli::{
+        get_config_path, load_config, save_config, AppConfig, Cli, Commands, ComposeSpec,
+        ConfigAction,
+    },
     docker::DockerProcessor,
+    kubernetes::export_kubernetes,
     podman::PodmanProcessor,
+    projects::group_by_project,
+    quadlet::export_quadlet,
+    run_script::{export_run_script, ContainerEngine},
     security::validate_output_path,
     validation::{format_validation_report, Validator},
     AutoComposeError, ComposeFile, Result,
 };
 use dialoguer::{theme::ColorfulTheme, MultiSelect};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 fn matches_pattern(text: &str, pattern: &str) -> bool {
     // Convert wildcard pattern to regex
@@ -185,10 +194,75 @@ fn should_include_container_podman(
     should_include
 }
 
+/// Keeps the top-level networks or volumes a compose file refers to.
+fn select_resources(
+    all: &HashMap<String, serde_yaml::Value>,
+    used: &HashSet<String>,
+    only_used: bool,
+) -> HashMap<String, serde_yaml::Value> {
+    all.iter()
+        .filter(|(name, _)| !only_used || used.contains(*name))
+        .map(|(name, definition)| (name.clone(), definition.clone()))
+        .collect()
+}
+
+/// `docker-compose.yml` becomes `docker-compose.<project>.yml` when the
+/// containers span several compose projects.
+fn project_output_path(output: &Path, project: Option<&str>) -> PathBuf {
+    let Some(project) = project else {
+        return output.to_path_buf();
+    };
+    let stem = output.file_stem().and_then(|s| s.to_str()).unwrap_or("docker-compose");
+    let file_name = match output.extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}.{}.{}", stem, project, extension),
+        None => format!("{}.{}", stem, project),
+    };
+    output.with_file_name(file_name)
+}
+
+/// Quadlet units go to a directory: `--output` itself, unless it still names
+/// a compose file, in which case a `quadlet` directory next to it is used.
+fn quadlet_output_dir(output: &Path, project: Option<&str>) -> PathBuf {
+    let dir = match output.extension().and_then(|e| e.to_str()) {
+        Some("yml" | "yaml" | "json" | "toml") => output.with_file_name("quadlet"),
+        _ => output.to_path_buf(),
+    };
+    match project {
+        Some(project) => dir.join(project),
+        None => dir,
+    }
+}
+
+async fn write_quadlet_units(compose_file: &ComposeFile, dir: &Path, dry_run: bool) -> Result<()> {
+    let export = export_quadlet(compose_file);
+    for warning in &export.warnings {
+        eprintln!("Warning: left out of the Quadlet units, {}", warning);
+    }
+
+    if dry_run {
+        for file in &export.files {
+            println!("=== DRY RUN - {} ===", file.name);
+            println!("{}", file.contents);
+        }
+        println!("=== END DRY RUN ===");
+        return Ok(());
+    }
+
+    // Validate output path before writing
+    let safe_dir = validate_output_path(dir)?;
+    tokio::fs::create_dir_all(&safe_dir).await?;
+    for file in &export.files {
+        tokio::fs::write(safe_dir.join(&file.name), &file.contents).await?;
+    }
+    println!("Quadlet units generated in {} ({} files)", safe_dir.display(), export.files.len());
+    Ok(())
+}
+
 fn format_compose_output(
     compose_file: &ComposeFile,
     format: Option<autocompose::cli::OutputFormat>,
     compact: bool,
+    engine: ContainerEngine,
 ) -> Result<String> {
     match format {
         Some(autocompose::cli::OutputFormat::Json) => {
@@ -210,6 +284,16 @@ fn format_compose_output(
                 ))
             })
         }
+        Some(autocompose::cli::OutputFormat::Kubernetes) => {
+            let export = export_kubernetes(compose_file);
+            for warning in &export.warnings {
+                eprintln!("Warning: left out of the Kubernetes manifests, {}", warning);
+            }
+            export.to_yaml()
+        }
+        Some(autocompose::cli::OutputFormat::RunScript) => {
+            Ok(export_run_script(compose_file, engine))
+        }
         _ => {
             if compact {
                 // For YAML compact mode, use minimal formatting
@@ -259,46 +343,13 @@ fn interactive_container_selection<T: Clone>(
     Ok(selected_containers)
 }
 
-async fn handle_docker_command(mut args: autocompose::cli::DockerArgs) -> Result<()> {
-    // Load configuration and apply defaults
-    let config = load_config().unwrap_or_default();
-    
-    // Enable debug output if requested
-    let debug_enabled = args.debug || args.verbose > 0;
-    if debug_enabled {
-        let level = match args.verbose {
-            0 if args.debug => "DEBUG",
-            1 => "INFO",
-            2 => "DEBUG",
-            _ => "TRACE",
-        };
-        eprintln!("[{}] Starting autocompose docker command", level);
-        eprintln!("[{}] Configuration loaded from: {:?}", level, get_config_path().ok());
-    }
-    
-    // Apply config defaults if CLI args not provided
-    if args.compose_version == "3.9" { // Check if it's the CLI default
-        args.compose_version = config.default_compose_version.clone();
-    }
-    if args.output == std::path::PathBuf::from("docker-compose.yml") {
-        args.output = config.default_output.clone();
-    }
-    if args.format.is_none() {
-        args.format = match config.default_format.as_str() {
-            "json" => Some(autocompose::cli::OutputFormat::Json),
-            "toml" => Some(autocompose::cli::OutputFormat::Toml),
-            _ => Some(autocompose::cli::OutputFormat::Yaml),
-        };
-    }
-    
-    // Handle Docker connection options
-    let processor = if let Some(docker_host) = &args.docker_host {
-        DockerProcessor::new_with_host(docker_host)?
-    } else if let Some(context) = &args.context {
-        DockerProcessor::new_with_context(context)?
-    } else {
-        DockerProcessor::new()?
-    };
+/// Lists the containers to export, after the state, label and interactive
+/// selections. Empty when nothing is left to export.
+async fn select_docker_containers(
+    processor: &DockerProcessor,
+    args: &autocompose::cli::DockerArgs,
+    debug_enabled: bool,
+) -> Result<Vec<ContainerSummary>> {
     // If --all is specified, include all containers regardless of running_only
     let include_all = args.all || !args.running_only;
     if debug_enabled {
@@ -346,7 +397,7 @@ async fn handle_docker_command(mut args: autocompose::cli::DockerArgs) -> Result
 
     if containers.is_empty() {
         println!("No containers found matching the specified filters.");
-        return Ok(());
+        return Ok(Vec::new());
     }
 
     // Interactive mode
@@ -371,18 +422,84 @@ async fn handle_docker_command(mut args: autocompose::cli::DockerArgs) -> Result
         
         if containers.is_empty() {
             println!("No containers selected.");
-            return Ok(());
+            return Ok(Vec::new());
         }
     }
 
-    println!("Processing {} containers...", containers.len());
-    let (services, networks, volumes) = if args.include_sensitive {
-        processor.process_containers_parallel_with_options(
-            containers, 
-            autocompose::docker::ProcessingOptions { include_sensitive: true }
-        ).await?
+    Ok(containers)
+}
+
+async fn handle_docker_command(mut args: autocompose::cli::DockerArgs) -> Result<()> {
+    // Load configuration and apply defaults
+    let config = load_config().unwrap_or_default();
+    
+    // Enable debug output if requested
+    let debug_enabled = args.debug || args.verbose > 0;
+    if debug_enabled {
+        let level = match args.verbose {
+            0 if args.debug => "DEBUG",
+            1 => "INFO",
+            2 => "DEBUG",
+            _ => "TRACE",
+        };
+        eprintln!("[{}] Starting autocompose docker command", level);
+        eprintln!("[{}] Configuration loaded from: {:?}", level, get_config_path().ok());
+    }
+    
+    // Apply config defaults if CLI args not provided
+    if args.compose_version == "3.9" { // Check if it's the CLI default
+        args.compose_version = config.default_compose_version.clone();
+    }
+    if args.output == std::path::PathBuf::from("docker-compose.yml") {
+        args.output = config.default_output.clone();
+    }
+    if args.format.is_none() {
+        args.format = match config.default_format.as_str() {
+            "json" => Some(autocompose::cli::OutputFormat::Json),
+            "toml" => Some(autocompose::cli::OutputFormat::Toml),
+            _ => Some(autocompose::cli::OutputFormat::Yaml),
+        };
+    }
+    
+    // Handle Docker connection options
+    let processor = if let Some(docker_host) = &args.docker_host {
+        DockerProcessor::new_with_host(docker_host)?
+    } else if let Some(context) = &args.context {
+        DockerProcessor::new_with_context(context)?
+    } else {
+        DockerProcessor::new()?
+    };
+    let options = autocompose::docker::ProcessingOptions {
+        include_sensitive: args.include_sensitive,
+        external: args.external,
+        image_preference: args.prefer_image,
+        cache_image_info: config.performance.cache_image_info,
+        pin_digests: args.pin_digests,
+        keep_image_defaults: args.keep_image_defaults,
+        keep_daemon_defaults: args.keep_daemon_defaults,
+        ..Default::default()
+    };
+    let (services, networks, volumes, secrets, configs) = if args.swarm {
+        if debug_enabled {
+            eprintln!("[DEBUG] Fetching Swarm services");
+        }
+        let stack = processor.process_swarm_services(options).await?;
+        if stack.services.is_empty() {
+            println!("No Swarm services found.");
+            return Ok(());
+        }
+        println!("Processing {} Swarm services...", stack.services.len());
+        (stack.services, stack.networks, stack.volumes, stack.secrets, stack.configs)
     } else {
-        processor.process_containers_parallel(containers).await?
+        let containers = select_docker_containers(&processor, &args, debug_enabled).await?;
+        if containers.is_empty() {
+            return Ok(());
+        }
+        println!("Processing {} containers...", containers.len());
+        let (services, networks, volumes) = processor
+            .process_containers_parallel_with_options(containers, options)
+            .await?;
+        (services, networks, volumes, HashMap::new(), HashMap::new())
     };
 
     // Apply filters from config and command line
@@ -413,8 +530,9 @@ async fn handle_docker_command(mut args: autocompose::cli::DockerArgs) -> Result
                         cpus: Some("0.5".to_string()),
                         memory: Some("512M".to_string()),
                     }),
+                    ..Default::default()
                 }),
-                placement: None,
+                ..Default::default()
             });
         }
         
@@ -422,41 +540,93 @@ async fn handle_docker_command(mut args: autocompose::cli::DockerArgs) -> Result
             filtered_services.insert(name, service);
         }
     }
-    
-    let compose_file = ComposeFile {
-        version: args.compose_version.clone(),
-        services: filtered_services,
-        networks: if networks.is_empty() || (!args.include_networks && !args.separate_networks) {
-            None
-        } else {
-            Some(networks)
-        },
-        volumes: if volumes.is_empty() || (!args.include_volumes && !args.separate_volumes) {
-            None
+
+    let mut projects = group_by_project(filtered_services);
+    if let Some(selected) = &args.project {
+        projects.retain(|project| project.name.as_deref() == Some(selected.as_str()));
+        if projects.is_empty() {
+            println!("No containers found for compose project '{}'.", selected);
+            return Ok(());
+        }
+    }
+    // Each file only declares what its own services use once the host is split up
+    let split = projects.len() > 1 || args.project.is_some();
+    let multiple_files = projects.len() > 1;
+    let include_networks = args.include_networks || args.separate_networks;
+    let include_volumes = args.include_volumes || args.separate_volumes;
+
+    for project in projects {
+        let (used_networks, used_volumes) = project.resources();
+        let project_networks = select_resources(&networks, &used_networks, split);
+        let project_volumes = select_resources(&volumes, &used_volumes, split);
+        let (used_secrets, used_configs) = project.secrets_and_configs();
+        let project_secrets = select_resources(&secrets, &used_secrets, true);
+        let project_configs = select_resources(&configs, &used_configs, true);
+        let output = if multiple_files {
+            project_output_path(&args.output, project.name.as_deref())
         } else {
-            Some(volumes)
-        },
-    };
+            args.output.clone()
+        };
+
+        let compose_file = ComposeFile {
+            version: (args.spec == ComposeSpec::Legacy).then(|| args.compose_version.clone()),
+            name: project.name.clone().filter(|_| args.spec == ComposeSpec::Latest),
+            services: project.services,
+            networks: if project_networks.is_empty() || !include_networks {
+                None
+            } else {
+                Some(project_networks)
+            },
+            volumes: if project_volumes.is_empty() || !include_volumes {
+                None
+            } else {
+                Some(project_volumes)
+            },
+            secrets: Some(project_secrets).filter(|s| !s.is_empty()),
+            configs: Some(project_configs).filter(|c| !c.is_empty()),
+        };
 
-    let content = format_compose_output(&compose_file, args.format.clone(), args.compact)?;
+        if matches!(args.format, Some(autocompose::cli::OutputFormat::Quadlet)) {
+            let project_dir = project.name.as_deref().filter(|_| multiple_files);
+            let dir = quadlet_output_dir(&args.output, project_dir);
+            write_quadlet_units(&compose_file, &dir, args.dry_run || args.preview).await?;
+            continue;
+        }
 
-    if args.dry_run || args.preview {
-        println!("=== DRY RUN - Generated Docker Compose ===");
-        println!("{}", content);
-        println!("=== END DRY RUN ===");
-    } else {
-        // Validate output path before writing
-        let safe_path = validate_output_path(&args.output)?;
-        tokio::fs::write(&safe_path, content).await?;
-        println!("Docker Compose file generated: {}", safe_path.display());
+        let content = format_compose_output(
+            &compose_file,
+            args.format.clone(),
+            args.compact,
+            ContainerEngine::Docker,
+        )?;
 
-        let validator = Validator::new(config.validation.check_best_practices, Some(args.compose_version));
-        let validation_report = validator.validate_compose_object(&compose_file);
+        if args.dry_run || args.preview {
+            match &project.name {
+                Some(name) => println!("=== DRY RUN - Generated Docker Compose ({}) ===", name),
+                None => println!("=== DRY RUN - Generated Docker Compose ==="),
+            }
+            println!("{}", content);
+            println!("=== END DRY RUN ===");
+        } else {
+            // Validate output path before writing
+            let safe_path = validate_output_path(&output)?;
+            tokio::fs::write(&safe_path, content).await?;
+            println!("Docker Compose file generated: {}", safe_path.display());
+            if let (Some(name), Some(dir)) = (&project.name, &project.working_dir) {
+                println!("  compose project '{}' was originally run from {}", name, dir);
+            }
 
-        if !validation_report.warnings.is_empty() || !validation_report.suggestions.is_empty() {
-            println!("\n=== Validation Report ===");
-            let report_text = format_validation_report(&validation_report, "text")?;
-            println!("{}", report_text);
+            let validator = Validator::new(
+                config.validation.check_best_practices,
+                compose_file.version.clone(),
+            );
+            let validation_report = validator.validate_compose_object(&compose_file);
+
+            if !validation_report.warnings.is_empty() || !validation_report.suggestions.is_empty() {
+                println!("\n=== Validation Report ===");
+                let report_text = format_validation_report(&validation_report, "text")?;
+                println!("{}", report_text);
+            }
         }
     }
 
@@ -490,22 +660,6 @@ async fn handle_podman_command(mut args: autocompose::cli::PodmanArgs) -> Result
         return Ok(());
     }
     
-    // Apply Podman-specific options
-    if args.include_pods {
-        // Add pod IDs to the list
-        if let Ok(output) = tokio::process::Command::new("podman")
-            .args(["pod", "ps", "-q"])
-            .output()
-            .await
-        {
-            let pod_ids = String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>();
-            container_ids.extend(pod_ids);
-        }
-    }
-
     // Interactive mode for Podman
     if args.interactive {
         // Get container details for display
@@ -547,8 +701,18 @@ async fn handle_podman_command(mut args: autocompose::cli::PodmanArgs) -> Result
     }
 
     println!("Processing {} containers...", container_ids.len());
-    let (services, networks, volumes) =
-        processor.process_containers_parallel(container_ids).await?;
+    let (services, networks, volumes) = processor.process_containers_parallel_with_options(
+        container_ids,
+        autocompose::docker::ProcessingOptions {
+            include_sensitive: args.include_sensitive,
+            external: args.external,
+            pin_digests: args.pin_digests,
+            keep_image_defaults: args.keep_image_defaults,
+            keep_daemon_defaults: args.keep_daemon_defaults,
+            include_pods: args.include_pods,
+            ..Default::default()
+        }
+    ).await?;
 
     // Apply filters from config and command line
     let mut filtered_services = HashMap::new();
@@ -575,8 +739,9 @@ async fn handle_podman_command(mut args: autocompose::cli::PodmanArgs) -> Result
                         cpus: Some("0.5".to_string()),
                         memory: Some("512M".to_string()),
                     }),
+                    ..Default::default()
                 }),
-                placement: None,
+                ..Default::default()
             });
         }
         
@@ -584,41 +749,90 @@ async fn handle_podman_command(mut args: autocompose::cli::PodmanArgs) -> Result
             filtered_services.insert(name, service);
         }
     }
-    
-    let compose_file = ComposeFile {
-        version: args.compose_version.clone(),
-        services: filtered_services,
-        networks: if networks.is_empty() || (!args.include_networks && !args.separate_networks) {
-            None
-        } else {
-            Some(networks)
-        },
-        volumes: if volumes.is_empty() || (!args.include_volumes && !args.separate_volumes) {
-            None
+
+    let mut projects = group_by_project(filtered_services);
+    if let Some(selected) = &args.project {
+        projects.retain(|project| project.name.as_deref() == Some(selected.as_str()));
+        if projects.is_empty() {
+            println!("No containers found for compose project '{}'.", selected);
+            return Ok(());
+        }
+    }
+    // Each file only declares what its own services use once the host is split up
+    let split = projects.len() > 1 || args.project.is_some();
+    let multiple_files = projects.len() > 1;
+    let include_networks = args.include_networks || args.separate_networks;
+    let include_volumes = args.include_volumes || args.separate_volumes;
+
+    for project in projects {
+        let (used_networks, used_volumes) = project.resources();
+        let project_networks = select_resources(&networks, &used_networks, split);
+        let project_volumes = select_resources(&volumes, &used_volumes, split);
+        let output = if multiple_files {
+            project_output_path(&args.output, project.name.as_deref())
         } else {
-            Some(volumes)
-        },
-    };
+            args.output.clone()
+        };
+
+        let compose_file = ComposeFile {
+            version: (args.spec == ComposeSpec::Legacy).then(|| args.compose_version.clone()),
+            name: project.name.clone().filter(|_| args.spec == ComposeSpec::Latest),
+            services: project.services,
+            networks: if project_networks.is_empty() || !include_networks {
+                None
+            } else {
+                Some(project_networks)
+            },
+            volumes: if project_volumes.is_empty() || !include_volumes {
+                None
+            } else {
+                Some(project_volumes)
+            },
+            secrets: None,
+            configs: None,
+        };
 
-    let content = format_compose_output(&compose_file, args.format.clone(), args.compact)?;
+        if matches!(args.format, Some(autocompose::cli::OutputFormat::Quadlet)) {
+            let project_dir = project.name.as_deref().filter(|_| multiple_files);
+            let dir = quadlet_output_dir(&args.output, project_dir);
+            write_quadlet_units(&compose_file, &dir, args.dry_run || args.preview).await?;
+            continue;
+        }
 
-    if args.dry_run || args.preview {
-        println!("=== DRY RUN - Generated Docker Compose ===");
-        println!("{}", content);
-        println!("=== END DRY RUN ===");
-    } else {
-        // Validate output path before writing
-        let safe_path = validate_output_path(&args.output)?;
-        tokio::fs::write(&safe_path, content).await?;
-        println!("Docker Compose file generated: {}", safe_path.display());
+        let content = format_compose_output(
+            &compose_file,
+            args.format.clone(),
+            args.compact,
+            ContainerEngine::Podman,
+        )?;
 
-        let validator = Validator::new(config.validation.check_best_practices, Some(args.compose_version));
-        let validation_report = validator.validate_compose_object(&compose_file);
+        if args.dry_run || args.preview {
+            match &project.name {
+                Some(name) => println!("=== DRY RUN - Generated Docker Compose ({}) ===", name),
+                None => println!("=== DRY RUN - Generated Docker Compose ==="),
+            }
+            println!("{}", content);
+            println!("=== END DRY RUN ===");
+        } else {
+            // Validate output path before writing
+            let safe_path = validate_output_path(&output)?;
+            tokio::fs::write(&safe_path, content).await?;
+            println!("Docker Compose file generated: {}", safe_path.display());
+            if let (Some(name), Some(dir)) = (&project.name, &project.working_dir) {
+                println!("  compose project '{}' was originally run from {}", name, dir);
+            }
 
-        if !validation_report.warnings.is_empty() || !validation_report.suggestions.is_empty() {
-            println!("\n=== Validation Report ===");
-            let report_text = format_validation_report(&validation_report, "text")?;
-            println!("{}", report_text);
+            let validator = Validator::new(
+                config.validation.check_best_practices,
+                compose_file.version.clone(),
+            );
+            let validation_report = validator.validate_compose_object(&compose_file);
+
+            if !validation_report.warnings.is_empty() || !validation_report.suggestions.is_empty() {
+                println!("\n=== Validation Report ===");
+                let report_text = format_validation_report(&validation_report, "text")?;
+                println!("{}", report_text);
+            }
         }
     }
 
@@ -638,15 +852,17 @@ async fn handle_validate_command(args: autocompose::cli::ValidateArgs) -> Result
             autocompose::cli::OutputFormat::Json => "json",
             autocompose::cli::OutputFormat::Yaml => "yaml",
             autocompose::cli::OutputFormat::Toml => "toml",
+            // Only the compose formats have a report layout of their own
+            _ => "text",
         })
         .unwrap_or("text");
 
     let output = format_validation_report(&report, format)?;
     println!("{}", output);
 
-    // In strict mode, fail if there are any warnings or suggestions
+    // In strict mode, fail if there are any warnings or actionable suggestions
     if args.strict {
-        if !report.warnings.is_empty() || !report.suggestions.is_empty() {
+        if report.fails_strict() {
             eprintln!("\n❌ Validation failed in strict mode due to warnings or suggestions");
             std::process::exit(1);
         }
@@ -209,7 +209,8 @@ async fn main() -> Result<()> {
     let (services, networks, volumes) = processor.process_containers_parallel(containers).await?;
 
     let compose_file = ComposeFile {
-        version: args.compose_version.clone(),
+        version: Some(args.compose_version.clone()),
+        name: None,
         services,
         networks: if networks.is_empty() {
             None
@@ -221,6 +222,8 @@ async fn main() -> Result<()> {
         } else {
             Some(volumes)
         },
+        secrets: None,
+        configs: None,
     };
 
     if !args.dry_run {
@@ -238,7 +238,8 @@ async fn main() -> Result<()> {
         processor.process_containers_parallel(container_ids).await?;
 
     let compose_file = ComposeFile {
-        version: args.compose_version.clone(),
+        version: Some(args.compose_version.clone()),
+        name: None,
         services,
         networks: if networks.is_empty() {
             None
@@ -250,6 +251,8 @@ async fn main() -> Result<()> {
         } else {
             Some(volumes)
         },
+        secrets: None,
+        configs: None,
     };
 
     if !args.dry_run {
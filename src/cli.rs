@@ -10,7 +10,7 @@ Contact: contact@olympus-chain.fr
 
 use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -47,6 +47,9 @@ pub struct DockerArgs {
     #[arg(short = 'V', long = "version", default_value = "3.9")]
     pub compose_version: String,
 
+    #[arg(long, value_enum, default_value_t = ComposeSpec::Legacy, help = "Compose file flavour; latest leaves out the obsolete version key")]
+    pub spec: ComposeSpec,
+
     #[arg(short, long, help = "Only include running containers")]
     pub running_only: bool,
 
@@ -116,6 +119,27 @@ pub struct DockerArgs {
     #[arg(long, help = "Include volume definitions in output")]
     pub include_volumes: bool,
 
+    #[arg(long, value_enum, default_value_t = ExternalMode::Auto, help = "Declare networks and volumes as external (auto detects ownership)")]
+    pub external: ExternalMode,
+
+    #[arg(long, value_enum, default_value_t = ImagePreference::Tag, help = "Reference to use when an image ID has to be resolved")]
+    pub prefer_image: ImagePreference,
+
+    #[arg(long, help = "Pin images to their registry digest (repo:tag@sha256:...)")]
+    pub pin_digests: bool,
+
+    #[arg(long, help = "Keep settings identical to the image defaults (ENV, CMD, labels...)")]
+    pub keep_image_defaults: bool,
+
+    #[arg(long, help = "Keep settings identical to the engine defaults (logging, runtime...)")]
+    pub keep_daemon_defaults: bool,
+
+    #[arg(long, help = "Only generate the compose file of this compose project")]
+    pub project: Option<String>,
+
+    #[arg(long, help = "Export Swarm services as a stack file instead of containers")]
+    pub swarm: bool,
+
     #[arg(long, help = "Docker host to connect to")]
     pub docker_host: Option<String>,
 
@@ -143,6 +167,9 @@ pub struct PodmanArgs {
     #[arg(short = 'V', long = "version", default_value = "3.9")]
     pub compose_version: String,
 
+    #[arg(long, value_enum, default_value_t = ComposeSpec::Legacy, help = "Compose file flavour; latest leaves out the obsolete version key")]
+    pub spec: ComposeSpec,
+
     #[arg(short, long, help = "Only include running containers")]
     pub running_only: bool,
 
@@ -206,7 +233,22 @@ pub struct PodmanArgs {
     #[arg(long, help = "Add resource limits to services")]
     pub resource_limits: bool,
 
-    #[arg(long, help = "Include pods (Podman specific)")]
+    #[arg(long, value_enum, default_value_t = ExternalMode::Auto, help = "Declare networks and volumes as external (auto detects ownership)")]
+    pub external: ExternalMode,
+
+    #[arg(long, help = "Pin images to their registry digest (repo:tag@sha256:...)")]
+    pub pin_digests: bool,
+
+    #[arg(long, help = "Keep settings identical to the image defaults (ENV, CMD, labels...)")]
+    pub keep_image_defaults: bool,
+
+    #[arg(long, help = "Keep settings identical to the engine defaults (logging, runtime...)")]
+    pub keep_daemon_defaults: bool,
+
+    #[arg(long, help = "Only generate the compose file of this compose project")]
+    pub project: Option<String>,
+
+    #[arg(long, help = "Export pods as services sharing one network namespace (Podman specific)")]
     pub include_pods: bool,
 
     #[arg(long, help = "Use rootless mode (Podman specific)")]
@@ -262,7 +304,7 @@ pub struct ValidateArgs {
     #[arg(long, help = "Check for best practices")]
     pub check_best_practices: bool,
 
-    #[arg(long, help = "Validate against specific compose version")]
+    #[arg(long, help = "Validate against specific compose version (latest for the Compose Specification)")]
     pub compose_version: Option<String>,
 
     #[arg(long, help = "Output format for validation results")]
@@ -277,6 +319,53 @@ pub enum OutputFormat {
     Yaml,
     Json,
     Toml,
+    /// Podman Quadlet units, written to the --output directory
+    Quadlet,
+    /// Kubernetes manifests as a multi-document YAML file
+    Kubernetes,
+    /// Shell script of `docker run` / `podman run` commands
+    RunScript,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExternalMode {
+    /// External when not created by the exported compose project
+    #[default]
+    Auto,
+    /// Every network and volume is external
+    Always,
+    /// Networks and volumes are always defined in the file
+    Never,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ComposeSpec {
+    /// Compose file format 3.x, with the `version` key
+    #[default]
+    Legacy,
+    /// Compose Specification: no `version`, the project `name` is set
+    Latest,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ImagePreference {
+    /// Prefer a repository tag, falling back to a digest
+    #[default]
+    Tag,
+    /// Prefer a repository digest, falling back to a tag
+    Digest,
+}
+
+impl ExternalMode {
+    /// Decides whether a network or volume is declared external, given the
+    /// compose project that created it and the projects of the containers using it.
+    pub fn applies(self, owner: Option<&str>, users: &HashSet<Option<String>>) -> bool {
+        match self {
+            ExternalMode::Auto => crate::is_external_resource(owner, users),
+            ExternalMode::Always => true,
+            ExternalMode::Never => false,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -387,6 +476,25 @@ mod tests {
         assert!(config.performance.parallel_processing);
     }
 
+    #[test]
+    fn test_external_mode() {
+        let labels = [("com.docker.compose.project".to_string(), "web".to_string())].into();
+        let owner = crate::compose_project(&labels);
+        assert_eq!(owner.as_deref(), Some("web"));
+
+        let shared: HashSet<Option<String>> =
+            [Some("web".to_string()), Some("api".to_string())].into_iter().collect();
+        let own: HashSet<Option<String>> = [Some("web".to_string())].into_iter().collect();
+        let standalone: HashSet<Option<String>> = [None].into_iter().collect();
+
+        assert!(ExternalMode::Auto.applies(owner.as_deref(), &shared));
+        assert!(ExternalMode::Auto.applies(owner.as_deref(), &standalone));
+        assert!(ExternalMode::Auto.applies(None, &own));
+        assert!(!ExternalMode::Auto.applies(owner.as_deref(), &own));
+        assert!(ExternalMode::Always.applies(Some("web"), &own));
+        assert!(!ExternalMode::Never.applies(None, &shared));
+    }
+
     #[test]
     fn test_config_serialization() {
         let config = AppConfig::default();
@@ -0,0 +1,630 @@
+/*!
+Copyright (c) 2025 Olympus Chain SAS
+
+This software is licensed under the Olympus Chain Internal Source License (OCISL).
+You may read and modify this code for personal or internal non-commercial use only.
+Commercial use, redistribution, or reuse in other software is prohibited without prior written permission.
+
+Contact: contact@olympus-chain.fr
+*/
+
+use crate::{DependencyCondition, DependencyConfig, DependsOn, Service};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// What a container tells us at runtime about the containers it relies on.
+#[derive(Debug, Clone, Default)]
+pub struct ContainerRelations {
+    pub service: String,
+    pub id: String,
+    /// Names other containers may use to reach this one: container name,
+    /// hostname and network aliases.
+    pub identifiers: Vec<String>,
+    /// Target of `network_mode: container:<id|name>`.
+    pub network_container: Option<String>,
+    pub volumes_from: Vec<String>,
+    pub links: Vec<String>,
+    pub env_values: Vec<String>,
+    pub started_at: Option<String>,
+    /// Exited with status 0 and is not restarted by the engine, i.e. a one-shot job.
+    pub completed: bool,
+    /// Runs a healthcheck, its own or the image's. The service loses the
+    /// latter once image defaults are pruned.
+    pub healthcheck: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    pub depends_on: HashMap<String, Vec<String>>,
+    /// Cycles found while ordering services; the edge closing each cycle is left out.
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl ContainerRelations {
+    fn started_at(&self) -> Option<(i64, u32)> {
+        self.started_at.as_deref().and_then(parse_timestamp)
+    }
+}
+
+/// Builds `depends_on` for every service from the relations of the whole set
+/// of inspected containers.
+///
+/// `network_mode: container:`, `volumes_from` and links are hard requirements
+/// of the engine and always become dependencies. An environment value pointing
+/// at another container, as a URL, a `host:port` pair or its bare name, only
+/// counts when that container was started first.
+pub fn infer_dependencies(relations: &[ContainerRelations]) -> DependencyGraph {
+    let lookup = identifier_lookup(relations);
+    let resolve = |reference: &str| resolve_container(relations, &lookup, reference);
+
+    let mut edges: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for (index, relation) in relations.iter().enumerate() {
+        let mut targets = BTreeSet::new();
+
+        if let Some(container) = &relation.network_container {
+            targets.extend(resolve(container));
+        }
+        for source in &relation.volumes_from {
+            // volumes_from entries may carry an access mode suffix
+            let name = source
+                .strip_suffix(":ro")
+                .or_else(|| source.strip_suffix(":rw"))
+                .unwrap_or(source);
+            targets.extend(resolve(name));
+        }
+        for link in &relation.links {
+            // Docker reports links as "/target:/container/alias"
+            let name = link.split(':').next().unwrap_or(link);
+            targets.extend(resolve(name));
+        }
+
+        let started_at = relation.started_at();
+        for host in relation.env_values.iter().flat_map(|v| env_hosts(v)) {
+            let Some(target) = lookup.get(host).copied() else {
+                continue;
+            };
+            let started_first = match (relations[target].started_at(), started_at) {
+                (Some(target_start), Some(own_start)) => target_start <= own_start,
+                _ => false,
+            };
+            if started_first {
+                targets.insert(target);
+            }
+        }
+
+        targets.remove(&index);
+        let services: BTreeSet<String> = targets
+            .into_iter()
+            .map(|target| relations[target].service.clone())
+            .filter(|service| *service != relation.service)
+            .collect();
+        if !services.is_empty() {
+            edges
+                .entry(relation.service.clone())
+                .or_default()
+                .extend(services);
+        }
+    }
+
+    let cycles = break_cycles(&mut edges);
+
+    DependencyGraph {
+        depends_on: edges
+            .into_iter()
+            .filter(|(_, deps)| !deps.is_empty())
+            .map(|(service, deps)| (service, deps.into_iter().collect()))
+            .collect(),
+        cycles,
+    }
+}
+
+/// Fills `depends_on` on the generated services, warning about any cycle
+/// that had to be left out.
+pub fn apply_dependencies(
+    services: &mut HashMap<String, Service>,
+    relations: &[ContainerRelations],
+) {
+    let graph = infer_dependencies(relations);
+
+    for cycle in &graph.cycles {
+        eprintln!(
+            "Warning: dependency cycle {} left out of depends_on",
+            cycle.join(" -> ")
+        );
+    }
+
+    let completed: BTreeSet<&str> = relations
+        .iter()
+        .filter(|r| r.completed)
+        .map(|r| r.service.as_str())
+        .collect();
+    let healthchecked: BTreeSet<&str> = relations
+        .iter()
+        .filter(|r| r.healthcheck)
+        .map(|r| r.service.as_str())
+        .collect();
+
+    for (name, deps) in graph.depends_on {
+        let conditions: HashMap<String, DependencyConfig> = deps
+            .iter()
+            .map(|dep| {
+                let condition = if completed.contains(dep.as_str()) {
+                    DependencyCondition::ServiceCompletedSuccessfully
+                } else if healthchecked.contains(dep.as_str())
+                    || services.get(dep).is_some_and(|s| s.healthcheck.is_some())
+                {
+                    DependencyCondition::ServiceHealthy
+                } else {
+                    DependencyCondition::ServiceStarted
+                };
+                let config = DependencyConfig {
+                    condition,
+                    restart: None,
+                    required: None,
+                };
+                (dep.clone(), config)
+            })
+            .collect();
+
+        // Keep the short form unless a dependency needs more than being started
+        let depends_on = if conditions
+            .values()
+            .all(|c| c.condition == DependencyCondition::ServiceStarted)
+        {
+            DependsOn::Simple(deps)
+        } else {
+            DependsOn::Extended(conditions)
+        };
+
+        if let Some(service) = services.get_mut(&name) {
+            service.depends_on = Some(depends_on);
+        }
+    }
+}
+
+/// Points `pid` and `ipc` modes of `container:<id|name>` at the service the
+/// container was exported as, warning when it is not part of the output.
+pub fn link_namespaces(services: &mut HashMap<String, Service>, relations: &[ContainerRelations]) {
+    let lookup = identifier_lookup(relations);
+    let exported: BTreeSet<String> = services.keys().cloned().collect();
+
+    for (name, service) in services.iter_mut() {
+        for (key, mode) in [("pid", &mut service.pid), ("ipc", &mut service.ipc)] {
+            let Some(reference) = mode.as_deref().and_then(|m| m.strip_prefix("container:")) else {
+                continue;
+            };
+            let target = resolve_container(relations, &lookup, reference)
+                .map(|index| &relations[index].service)
+                .filter(|target| exported.contains(*target) && *target != name);
+            match target {
+                Some(target) => *mode = Some(format!("service:{}", target)),
+                None => eprintln!(
+                    "Warning: {} of service {} shares the namespace of container {}, \
+                     which is not exported",
+                    key, name, reference
+                ),
+            }
+        }
+    }
+}
+
+/// Drops dependencies on services that are no longer part of the output,
+/// e.g. after filtering.
+pub fn retain_known_dependencies(services: &mut HashMap<String, Service>) {
+    let names: BTreeSet<String> = services.keys().cloned().collect();
+
+    for service in services.values_mut() {
+        if let Some(deps) = service.depends_on.as_mut() {
+            deps.retain(|dep| names.contains(dep));
+            if deps.is_empty() {
+                service.depends_on = None;
+            }
+        }
+    }
+}
+
+/// The container a name, ID or unambiguous ID prefix refers to.
+fn resolve_container(
+    relations: &[ContainerRelations],
+    lookup: &HashMap<&str, usize>,
+    reference: &str,
+) -> Option<usize> {
+    let reference = reference.trim_start_matches('/');
+    if reference.is_empty() {
+        return None;
+    }
+    lookup.get(reference).copied().or_else(|| {
+        // The engine accepts unambiguous ID prefixes
+        if reference.len() < 12 {
+            return None;
+        }
+        let mut matches = relations
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.id.starts_with(reference));
+        match (matches.next(), matches.next()) {
+            (Some((index, _)), None) => Some(index),
+            _ => None,
+        }
+    })
+}
+
+/// Maps every identifier to the container it names, dropping identifiers
+/// shared by several containers since they cannot be resolved reliably.
+fn identifier_lookup(relations: &[ContainerRelations]) -> HashMap<&str, usize> {
+    let mut lookup: HashMap<&str, Option<usize>> = HashMap::new();
+
+    for (index, relation) in relations.iter().enumerate() {
+        let short_id = &relation.id[..relation.id.len().min(12)];
+        let names = relation
+            .identifiers
+            .iter()
+            .map(|i| i.trim_start_matches('/'))
+            .chain([relation.id.as_str(), short_id, relation.service.as_str()])
+            .filter(|name| !name.is_empty())
+            .collect::<BTreeSet<_>>();
+
+        for name in names {
+            lookup
+                .entry(name)
+                .and_modify(|existing| {
+                    if *existing != Some(index) {
+                        *existing = None;
+                    }
+                })
+                .or_insert(Some(index));
+        }
+    }
+
+    lookup
+        .into_iter()
+        .filter_map(|(name, index)| index.map(|i| (name, i)))
+        .collect()
+}
+
+/// The hosts an environment value may point at: the host of a URL such as
+/// `postgres://app@db:5432/app`, of a `host:port` pair or a bare host name,
+/// also in comma-separated lists. Callers only keep the hosts naming a known
+/// container.
+fn env_hosts(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').filter_map(|entry| {
+        let entry = entry.trim();
+        let authority = match entry.split_once("://") {
+            Some((_, rest)) => rest.split(['/', '?', '#']).next()?,
+            None => entry,
+        };
+        let authority = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        };
+
+        let numeric_port =
+            port.is_some_and(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()));
+        let valid_port = numeric_port || port.is_none();
+        let valid_host = !host.is_empty()
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_');
+        (valid_port && valid_host).then_some(host)
+    })
+}
+
+/// Removes the edge closing each cycle until the graph is acyclic, and returns
+/// every cycle found, each starting and ending with the same service.
+fn break_cycles(edges: &mut BTreeMap<String, BTreeSet<String>>) -> Vec<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Visiting,
+        Done,
+    }
+
+    fn visit(
+        node: &str,
+        edges: &BTreeMap<String, BTreeSet<String>>,
+        state: &mut HashMap<String, State>,
+        stack: &mut Vec<String>,
+        back_edges: &mut Vec<(String, String)>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        state.insert(node.to_string(), State::Visiting);
+        stack.push(node.to_string());
+
+        for next in edges.get(node).into_iter().flatten() {
+            match state.get(next.as_str()) {
+                Some(State::Visiting) => {
+                    let start = stack.iter().position(|n| n == next).unwrap_or(0);
+                    let mut cycle = stack[start..].to_vec();
+                    cycle.push(next.clone());
+                    cycles.push(cycle);
+                    back_edges.push((node.to_string(), next.clone()));
+                }
+                Some(State::Done) => {}
+                None => visit(next, edges, state, stack, back_edges, cycles),
+            }
+        }
+
+        stack.pop();
+        state.insert(node.to_string(), State::Done);
+    }
+
+    let mut cycles = Vec::new();
+    let nodes: Vec<String> = edges.keys().cloned().collect();
+
+    // Search again after each removal until a pass finds no cycle at all
+    loop {
+        let mut state = HashMap::new();
+        let mut back_edges = Vec::new();
+        for node in &nodes {
+            if !state.contains_key(node) {
+                let mut stack = Vec::new();
+                visit(
+                    node,
+                    edges,
+                    &mut state,
+                    &mut stack,
+                    &mut back_edges,
+                    &mut cycles,
+                );
+            }
+        }
+        if back_edges.is_empty() {
+            return cycles;
+        }
+
+        for (from, to) in back_edges {
+            if let Some(deps) = edges.get_mut(&from) {
+                deps.remove(&to);
+            }
+        }
+    }
+}
+
+/// Parses an RFC 3339 timestamp as reported by Docker and Podman into
+/// seconds and nanoseconds since the epoch. The zero time of a container
+/// that never started yields `None`.
+fn parse_timestamp(value: &str) -> Option<(i64, u32)> {
+    let (date, rest) = value.split_once('T')?;
+    let mut date_parts = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (date_parts.next()??, date_parts.next()??, date_parts.next()??);
+    if year <= 1 {
+        return None;
+    }
+
+    let (time, offset_secs) = if let Some(time) = rest.strip_suffix('Z') {
+        (time, 0)
+    } else {
+        let split = rest.rfind(['+', '-'])?;
+        let (time, offset) = rest.split_at(split);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = offset[1..].split_once(':')?;
+        (time, sign * (hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60))
+    };
+
+    let (clock, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut clock_parts = clock.splitn(3, ':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) =
+        (clock_parts.next()??, clock_parts.next()??, clock_parts.next()??);
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<9}", &fraction[..fraction.len().min(9)]).parse().ok()?
+    };
+
+    // Days since the epoch for a proleptic Gregorian date
+    let (y, m) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    Some((days * 86_400 + hour * 3600 + minute * 60 + second - offset_secs, nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relations(service: &str, id: &str, started_at: &str) -> ContainerRelations {
+        ContainerRelations {
+            service: service.to_string(),
+            id: id.to_string(),
+            identifiers: vec![format!("/{}", service)],
+            started_at: Some(started_at.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("1970-01-01T00:00:00Z"), Some((0, 0)));
+        assert_eq!(
+            parse_timestamp("2024-03-01T12:00:00.5+02:00"),
+            parse_timestamp("2024-03-01T10:00:00.500000000Z")
+        );
+        assert_eq!(parse_timestamp("0001-01-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp("not a date"), None);
+    }
+
+    #[test]
+    fn test_infer_dependencies() {
+        let db = relations("db", "aaaaaaaaaaaa1111", "2024-03-01T10:00:00Z");
+        let cache = relations("cache", "bbbbbbbbbbbb2222", "2024-03-01T10:00:05Z");
+        let mut vpn = relations("vpn", "cccccccccccc3333", "2024-03-01T10:00:01Z");
+        vpn.identifiers.push("tunnel".to_string());
+        // DB_HOST=db, and a bare word that names no container
+        vpn.env_values = vec!["db".to_string(), "info".to_string()];
+
+        let mut web = relations("web", "dddddddddddd4444", "2024-03-01T10:00:02Z");
+        web.network_container = Some("cccccccccccc3333".to_string());
+        web.env_values = vec![
+            "postgres://app@db:5432/app".to_string(),
+            // Started after web, so only a coincidental mention
+            "cache:6379".to_string(),
+        ];
+        let mut worker = relations("worker", "eeeeeeeeeeee5555", "2024-03-01T10:00:03Z");
+        worker.volumes_from = vec!["web:ro".to_string()];
+        worker.links = vec!["/db:/worker/database".to_string()];
+
+        let graph = infer_dependencies(&[db, cache, vpn, web, worker]);
+
+        assert_eq!(graph.depends_on["web"], vec!["db".to_string(), "vpn".to_string()]);
+        assert_eq!(graph.depends_on["worker"], vec!["db".to_string(), "web".to_string()]);
+        assert_eq!(graph.depends_on["vpn"], vec!["db".to_string()]);
+        assert!(!graph.depends_on.contains_key("db"));
+        assert!(graph.cycles.is_empty());
+    }
+
+    #[test]
+    fn test_cycles_are_reported_not_emitted() {
+        let mut a = relations("a", "aaaaaaaaaaaa", "2024-03-01T10:00:00Z");
+        let mut b = relations("b", "bbbbbbbbbbbb", "2024-03-01T10:00:01Z");
+        a.volumes_from = vec!["b".to_string()];
+        b.volumes_from = vec!["a".to_string()];
+
+        let graph = infer_dependencies(&[a, b]);
+
+        assert_eq!(graph.cycles, vec![vec!["a".to_string(), "b".to_string(), "a".to_string()]]);
+        assert_eq!(graph.depends_on.get("a"), Some(&vec!["b".to_string()]));
+        assert!(!graph.depends_on.contains_key("b"));
+    }
+
+    #[test]
+    fn test_every_cycle_is_broken() {
+        // a -> b -> c -> a, with c -> b closing a second, shorter cycle
+        let mut edges: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for (from, to) in [("a", "b"), ("b", "c"), ("c", "a"), ("c", "b"), ("d", "a")] {
+            edges
+                .entry(from.to_string())
+                .or_default()
+                .insert(to.to_string());
+        }
+
+        let cycles = break_cycles(&mut edges);
+
+        assert_eq!(cycles.len(), 2);
+        assert!(break_cycles(&mut edges).is_empty());
+        assert!(edges["d"].contains("a"));
+    }
+
+    #[test]
+    fn test_env_hosts() {
+        let hosts = |value| env_hosts(value).collect::<Vec<_>>();
+
+        assert_eq!(hosts("postgres://app:secret@db:5432/app"), vec!["db"]);
+        assert_eq!(hosts("http://api/health"), vec!["api"]);
+        assert_eq!(hosts("redis:6379"), vec!["redis"]);
+        assert_eq!(hosts("db"), vec!["db"]);
+        assert_eq!(
+            hosts("kafka-1:9092, kafka-2:9092"),
+            vec!["kafka-1", "kafka-2"]
+        );
+        // Values that merely contain a colon are not hosts
+        assert!(hosts("debug:true").is_empty());
+        assert!(hosts("/var/run/app.sock").is_empty());
+    }
+
+    #[test]
+    fn test_link_namespaces() {
+        let service = |yaml: &str| -> Service { serde_yaml::from_str(yaml).unwrap() };
+        let mut services = HashMap::new();
+        services.insert("app".to_string(), service("image: app"));
+        services.insert(
+            "debug".to_string(),
+            service("image: busybox\npid: container:aaaaaaaaaaaa1111\nipc: container:gone"),
+        );
+        let app = relations("app", "aaaaaaaaaaaa1111", "2024-03-01T10:00:00Z");
+        let debug = relations("debug", "bbbbbbbbbbbb2222", "2024-03-01T10:00:01Z");
+
+        link_namespaces(&mut services, &[app, debug]);
+
+        assert_eq!(services["debug"].pid.as_deref(), Some("service:app"));
+        // Left as is when the container was not exported
+        assert_eq!(services["debug"].ipc.as_deref(), Some("container:gone"));
+    }
+
+    #[test]
+    fn test_image_healthcheck_survives_pruning() {
+        let healthcheck = crate::HealthCheck {
+            test: vec!["CMD".to_string(), "pg_isready".to_string()],
+            interval: None,
+            timeout: None,
+            retries: None,
+            start_period: None,
+        };
+        let mut db: Service = serde_yaml::from_str("image: postgres:16").unwrap();
+        db.healthcheck = Some(healthcheck.clone());
+        let image = crate::pruning::ImageDefaults {
+            healthcheck: Some(healthcheck),
+            ..Default::default()
+        };
+        crate::pruning::prune_image_defaults(&mut db, &image);
+        assert!(db.healthcheck.is_none());
+
+        let mut services = HashMap::new();
+        services.insert("db".to_string(), db);
+        services.insert(
+            "web".to_string(),
+            serde_yaml::from_str("image: app").unwrap(),
+        );
+        let mut db = relations("db", "aaaaaaaaaaaa", "2024-03-01T10:00:00Z");
+        db.healthcheck = true;
+        let mut web = relations("web", "bbbbbbbbbbbb", "2024-03-01T10:00:01Z");
+        web.env_values = vec!["db:5432".to_string()];
+
+        apply_dependencies(&mut services, &[db, web]);
+
+        let Some(DependsOn::Extended(deps)) = &services["web"].depends_on else {
+            panic!("expected long syntax for web");
+        };
+        assert_eq!(deps["db"].condition, DependencyCondition::ServiceHealthy);
+    }
+
+    #[test]
+    fn test_apply_dependencies_conditions() {
+        let service = |healthcheck: bool| -> Service {
+            serde_yaml::from_str(if healthcheck {
+                "image: postgres\nhealthcheck:\n  test: [CMD, pg_isready]"
+            } else {
+                "image: app"
+            })
+            .unwrap()
+        };
+        let mut services = HashMap::new();
+        services.insert("db".to_string(), service(true));
+        services.insert("migrate".to_string(), service(false));
+        services.insert("web".to_string(), service(false));
+        services.insert("worker".to_string(), service(false));
+
+        let db = relations("db", "aaaaaaaaaaaa", "2024-03-01T10:00:00Z");
+        let mut migrate = relations("migrate", "bbbbbbbbbbbb", "2024-03-01T10:00:01Z");
+        migrate.env_values = vec!["db:5432".to_string()];
+        migrate.completed = true;
+        let mut web = relations("web", "cccccccccccc", "2024-03-01T10:00:02Z");
+        web.env_values = vec![
+            "postgres://db/app".to_string(),
+            "http://migrate:8080/status".to_string(),
+        ];
+        let mut worker = relations("worker", "dddddddddddd", "2024-03-01T10:00:03Z");
+        worker.links = vec!["/web:/worker/web".to_string()];
+
+        apply_dependencies(&mut services, &[db, migrate, web, worker]);
+
+        let Some(DependsOn::Extended(web_deps)) = &services["web"].depends_on else {
+            panic!("expected long syntax for web");
+        };
+        assert_eq!(
+            web_deps["migrate"].condition,
+            DependencyCondition::ServiceCompletedSuccessfully
+        );
+        assert_eq!(web_deps["db"].condition, DependencyCondition::ServiceHealthy);
+        assert!(matches!(
+            &services["worker"].depends_on,
+            Some(DependsOn::Simple(deps)) if deps == &vec!["web".to_string()]
+        ));
+    }
+
+}
@@ -11,26 +11,183 @@ Contact: contact@olympus-chain.fr
 #![allow(deprecated)]
 
 use crate::{
-    filter_system_labels, normalize_duration_from_ns, sanitize_service_name,
-    security::filter_sensitive_env_vars, AutoComposeError, Deploy, HealthCheck, Logging,
-    NetworkConfig, Placement, ResourceLimits, Resources, Result, Service, ServiceNetworks,
-    UlimitConfig,
+    cli::{ExternalMode, ImagePreference},
+    compose_project,
+    dependencies::{apply_dependencies, link_namespaces, ContainerRelations},
+    expose_entry, filter_endpoint_aliases, filter_system_annotations, filter_system_labels,
+    format_duration_secs, format_size_bytes, image_platform, is_anonymous_volume, is_image_id,
+    memswap_limit, normalize_duration_from_ns, pin_image_digest,
+    projects::ComposeLabels,
+    pruning::{prune_daemon_defaults, prune_image_defaults, DaemonDefaults, ImageDefaults},
+    sanitize_service_name,
+    security::filter_sensitive_env_vars,
+    swarm::{external_definition, stack_service, SwarmStack},
+    tmpfs_entry, unpublished_ports, AutoComposeError, BindOptions, BlkioConfig, BlkioThrottle,
+    BlkioWeightDevice, Deploy, DeviceCount, DeviceReservation, HealthCheck, IpamDefinition,
+    IpamPool, Logging, NetworkConfig, NetworkDefinition, ResourceLimits, ResourceReservations,
+    Resources, Result, Service, ServiceNetworks, ServiceVolume, TmpfsOptions, UlimitConfig,
+    VolumeDefinition, VolumeMount, VolumeOptions,
 };
 use bollard::container::{InspectContainerOptions, ListContainersOptions};
-use bollard::models::{ContainerInspectResponse, ContainerSummary, RestartPolicyNameEnum};
+use bollard::models::{
+    ContainerInspectResponse, ContainerStateStatusEnum, ContainerSummary, HealthConfig,
+    ImageInspect, MountPoint, MountPointTypeEnum, MountTypeEnum, Network, RestartPolicyNameEnum,
+    SystemInfoCgroupVersionEnum, ThrottleDevice, Volume,
+};
+use bollard::query_parameters::{InspectNetworkOptions, ListNetworksOptions, ListServicesOptions};
 use bollard::Docker;
 use futures::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
 
 pub struct DockerProcessor {
     docker: Docker,
+    image_cache: ImageCache,
 }
 
+#[derive(Default)]
 pub struct ProcessingOptions {
     pub include_sensitive: bool,
+    pub external: ExternalMode,
+    pub image_preference: ImagePreference,
+    pub cache_image_info: bool,
+    pub pin_digests: bool,
+    pub keep_image_defaults: bool,
+    pub keep_daemon_defaults: bool,
+    /// Podman only: export pods as services sharing one network namespace
+    pub include_pods: bool,
+}
+
+/// One lookup per image ID; containers of the same image wait for the first.
+type ImageCache = Arc<Mutex<HashMap<String, Arc<OnceCell<Option<ImageInfo>>>>>>;
+
+/// The repository tags and digests an image is known by, its platform and
+/// the settings its containers inherit.
+#[derive(Clone, Debug, Default)]
+struct ImageInfo {
+    tags: Vec<String>,
+    digests: Vec<String>,
+    platform: Option<String>,
+    defaults: ImageDefaults,
+}
+
+impl From<ImageInspect> for ImageInfo {
+    fn from(image: ImageInspect) -> Self {
+        let known = |references: Option<Vec<String>>| {
+            references
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|r| !r.starts_with("<none>"))
+                .collect()
+        };
+
+        let config = image.config.unwrap_or_default();
+        let defaults = ImageDefaults {
+            env: ImageDefaults::parse_env(config.env.iter().flatten().map(|e| e.as_str())),
+            cmd: config.cmd,
+            entrypoint: config.entrypoint,
+            working_dir: config.working_dir.filter(|w| !w.is_empty()),
+            user: config.user.filter(|u| !u.is_empty()),
+            healthcheck: config.healthcheck.as_ref().map(DockerProcessor::health_check),
+            labels: config.labels.unwrap_or_default(),
+            stop_signal: config.stop_signal.filter(|s| !s.is_empty()),
+            exposed_ports: config
+                .exposed_ports
+                .iter()
+                .flat_map(|ports| ports.keys())
+                .map(|port| expose_entry(port))
+                .collect(),
+        };
+
+        Self {
+            tags: known(image.repo_tags),
+            digests: known(image.repo_digests),
+            platform: image_platform(
+                image.os.as_deref().unwrap_or_default(),
+                image.architecture.as_deref().unwrap_or_default(),
+                image.variant.as_deref().unwrap_or_default(),
+            ),
+            defaults,
+        }
+    }
+}
+
+impl ImageInfo {
+    fn pick(&self, preference: ImagePreference) -> Option<String> {
+        let tag = self.tags.first().cloned();
+        let digest = self.digests.first().cloned();
+
+        match preference {
+            ImagePreference::Tag => tag.or(digest),
+            ImagePreference::Digest => digest.or(tag),
+        }
+    }
+}
+
+/// Looks up the images behind containers to resolve IDs, pin digests, read
+/// their platform and prune inherited settings. Each image is inspected once
+/// per run, or once per processor when caching is enabled.
+#[derive(Clone)]
+struct ImageResolver {
+    docker: Docker,
+    preference: ImagePreference,
+    pin_digests: bool,
+    keep_image_defaults: bool,
+    cache: ImageCache,
+}
+
+impl ImageResolver {
+    async fn info(&self, image_id: &str) -> Option<ImageInfo> {
+        let cell = self
+            .cache
+            .lock()
+            .ok()?
+            .entry(image_id.to_string())
+            .or_default()
+            .clone();
+
+        cell.get_or_init(|| async {
+            match self.docker.inspect_image(image_id).await {
+                Ok(image) => Some(ImageInfo::from(image)),
+                Err(e) => {
+                    eprintln!("Warning: could not inspect image {}: {}", image_id, e);
+                    None
+                }
+            }
+        })
+        .await
+        .clone()
+    }
+}
+
+/// Adds an empty definition for each network or volume a container uses and
+/// records the project using it, for deciding later whether it is external.
+fn record_users(
+    names: Vec<String>,
+    project: &Option<String>,
+    definitions: &mut HashMap<String, Value>,
+    users: &mut HashMap<String, HashSet<Option<String>>>,
+) {
+    for name in names {
+        users
+            .entry(name.clone())
+            .or_default()
+            .insert(project.clone());
+        definitions
+            .entry(name)
+            .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
+    }
+}
+
+/// Network modes that share a namespace instead of attaching to a network.
+fn is_namespace_mode(mode: &str) -> bool {
+    matches!(mode, "host" | "none")
+        || mode.starts_with("container:")
+        || mode.starts_with("service:")
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -58,21 +215,28 @@ struct DockerConfig {
 }
 
 impl DockerProcessor {
+    fn from_docker(docker: Docker) -> Self {
+        Self {
+            docker,
+            image_cache: Arc::default(),
+        }
+    }
+
     pub fn new() -> Result<Self> {
         let docker = Docker::connect_with_local_defaults()?;
-        Ok(Self { docker })
+        Ok(Self::from_docker(docker))
     }
     
     pub fn new_with_host(docker_host: &str) -> Result<Self> {
         let docker = Docker::connect_with_http(docker_host, 120, bollard::API_DEFAULT_VERSION)?;
-        Ok(Self { docker })
+        Ok(Self::from_docker(docker))
     }
     
     pub fn new_with_context(context_name: &str) -> Result<Self> {
         // Try to read Docker context configuration
         if let Some(context_endpoint) = Self::read_docker_context(context_name)? {
             let docker = Docker::connect_with_http(&context_endpoint, 120, bollard::API_DEFAULT_VERSION)?;
-            Ok(Self { docker })
+            Ok(Self::from_docker(docker))
         } else {
             // Fallback to default if context not found
             eprintln!("Warning: Docker context '{}' not found, using default connection", context_name);
@@ -147,6 +311,30 @@ impl DockerProcessor {
         Ok(self.docker.list_containers(Some(list_options)).await?)
     }
 
+    /// Queries the daemon for the defaults a container falls back to,
+    /// assuming a stock configuration when the daemon does not say.
+    async fn daemon_defaults(&self) -> DaemonDefaults {
+        let mut defaults = DaemonDefaults::docker();
+        match self.docker.info().await {
+            Ok(info) => {
+                if let Some(driver) = info.logging_driver.filter(|d| !d.is_empty()) {
+                    defaults.log_driver = driver;
+                }
+                if let Some(runtime) = info.default_runtime.filter(|r| !r.is_empty()) {
+                    defaults.default_runtime = Some(runtime);
+                }
+                // cgroup v2 hosts give each container its own cgroup namespace
+                defaults.cgroupns_mode = match info.cgroup_version {
+                    Some(SystemInfoCgroupVersionEnum::_1) => Some("host".to_string()),
+                    Some(SystemInfoCgroupVersionEnum::_2) => Some("private".to_string()),
+                    _ => defaults.cgroupns_mode,
+                };
+            }
+            Err(e) => eprintln!("Warning: could not query docker info: {}", e),
+        }
+        defaults
+    }
+
     pub async fn process_containers_parallel(
         &self,
         containers: Vec<ContainerSummary>,
@@ -155,7 +343,8 @@ impl DockerProcessor {
         HashMap<String, Value>,
         HashMap<String, Value>,
     )> {
-        self.process_containers_parallel_with_options(containers, ProcessingOptions { include_sensitive: false }).await
+        self.process_containers_parallel_with_options(containers, ProcessingOptions::default())
+            .await
     }
     
     pub async fn process_containers_parallel_with_options(
@@ -168,47 +357,224 @@ impl DockerProcessor {
         HashMap<String, Value>,
     )> {
         let mut tasks = FuturesUnordered::new();
+        let resolver = ImageResolver {
+            docker: self.docker.clone(),
+            preference: options.image_preference,
+            pin_digests: options.pin_digests,
+            keep_image_defaults: options.keep_image_defaults,
+            cache: if options.cache_image_info {
+                self.image_cache.clone()
+            } else {
+                ImageCache::default()
+            },
+        };
 
         for container in containers {
             let docker_clone = self.docker.clone();
+            let resolver = resolver.clone();
             let include_sensitive = options.include_sensitive;
+            let project = container.labels.as_ref().and_then(compose_project);
             tasks.push(tokio::spawn(async move {
-                Self::process_single_container(docker_clone, container, include_sensitive).await
+                let result = Self::process_single_container(
+                    docker_clone,
+                    container,
+                    include_sensitive,
+                    resolver,
+                )
+                .await;
+                (project, result)
             }));
         }
 
         let mut services = HashMap::new();
         let mut networks = HashMap::new();
         let mut volumes = HashMap::new();
+        let mut network_users: HashMap<String, HashSet<Option<String>>> = HashMap::new();
+        let mut volume_users: HashMap<String, HashSet<Option<String>>> = HashMap::new();
+        let mut relations = Vec::new();
 
         while let Some(task_result) = tasks.next().await {
             match task_result {
-                Ok(Ok((service_name, service, nets, vols))) => {
+                Ok((project, Ok((service_name, service, nets, vols, container_relations)))) => {
                     services.insert(service_name, service);
-                    for net in nets {
-                        networks
-                            .entry(net)
-                            .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
-                    }
-                    for vol in vols {
-                        volumes
-                            .entry(vol)
-                            .or_insert_with(|| Value::Mapping(serde_yaml::Mapping::new()));
-                    }
+                    relations.push(container_relations);
+                    record_users(nets, &project, &mut networks, &mut network_users);
+                    record_users(vols, &project, &mut volumes, &mut volume_users);
                 }
-                Ok(Err(e)) => eprintln!("Error processing container: {:?}", e),
+                Ok((_, Err(e))) => eprintln!("Error processing container: {:?}", e),
                 Err(e) => eprintln!("Task failed: {:?}", e),
             }
         }
 
+        apply_dependencies(&mut services, &relations);
+        link_namespaces(&mut services, &relations);
+
+        if !options.keep_daemon_defaults {
+            let daemon_defaults = self.daemon_defaults().await;
+            for service in services.values_mut() {
+                prune_daemon_defaults(service, &daemon_defaults);
+            }
+        }
+
+        self.resolve_networks(&mut networks, &network_users, options.external)
+            .await?;
+        self.resolve_volumes(&mut volumes, &volume_users, options.external)
+            .await?;
+
         Ok((services, networks, volumes))
     }
 
+    /// Fills in the definition of each network, or marks it external.
+    async fn resolve_networks(
+        &self,
+        networks: &mut HashMap<String, Value>,
+        network_users: &HashMap<String, HashSet<Option<String>>>,
+        external: ExternalMode,
+    ) -> Result<()> {
+        let no_users = HashSet::new();
+
+        for (name, definition) in networks.iter_mut() {
+            let users = network_users.get(name).unwrap_or(&no_users);
+
+            // Predefined networks cannot be redefined by compose, only referenced
+            if Self::is_predefined_network(name) {
+                if external.applies(None, users) {
+                    *definition = serde_yaml::to_value(NetworkDefinition::external(name))?;
+                }
+                continue;
+            }
+
+            match self
+                .docker
+                .inspect_network(name, None::<InspectNetworkOptions>)
+                .await
+            {
+                Ok(network) => {
+                    let owner = network.labels.as_ref().and_then(compose_project);
+                    *definition = if external.applies(owner.as_deref(), users) {
+                        serde_yaml::to_value(NetworkDefinition::external(name))?
+                    } else {
+                        serde_yaml::to_value(Self::network_definition(network))?
+                    };
+                }
+                Err(e) => {
+                    eprintln!("Warning: could not inspect network {}: {}", name, e);
+                    if external == ExternalMode::Always {
+                        *definition = serde_yaml::to_value(NetworkDefinition::external(name))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fills in the definition of each volume, or marks it external.
+    async fn resolve_volumes(
+        &self,
+        volumes: &mut HashMap<String, Value>,
+        volume_users: &HashMap<String, HashSet<Option<String>>>,
+        external: ExternalMode,
+    ) -> Result<()> {
+        let no_users = HashSet::new();
+
+        for (name, definition) in volumes.iter_mut() {
+            let users = volume_users.get(name).unwrap_or(&no_users);
+
+            match self.docker.inspect_volume(name).await {
+                Ok(volume) => {
+                    let owner = compose_project(&volume.labels);
+                    *definition = if external.applies(owner.as_deref(), users) {
+                        serde_yaml::to_value(VolumeDefinition::external(name))?
+                    } else {
+                        serde_yaml::to_value(Self::volume_definition(volume))?
+                    };
+                }
+                Err(e) => {
+                    eprintln!("Warning: could not inspect volume {}: {}", name, e);
+                    if external == ExternalMode::Always {
+                        *definition = serde_yaml::to_value(VolumeDefinition::external(name))?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Exports the services of a Swarm from their specs, rather than from the
+    /// task containers running on this node. Fails unless the daemon is a
+    /// Swarm manager.
+    pub async fn process_swarm_services(&self, options: ProcessingOptions) -> Result<SwarmStack> {
+        let swarm_services = self
+            .docker
+            .list_services(None::<ListServicesOptions>)
+            .await?;
+        let network_names: HashMap<String, String> = self
+            .docker
+            .list_networks(None::<ListNetworksOptions>)
+            .await?
+            .into_iter()
+            .filter_map(|network| Some((network.id?, network.name?)))
+            .collect();
+
+        let mut stack = SwarmStack::default();
+        let mut network_users: HashMap<String, HashSet<Option<String>>> = HashMap::new();
+        let mut volume_users: HashMap<String, HashSet<Option<String>>> = HashMap::new();
+
+        for spec in swarm_services.iter().filter_map(|s| s.spec.as_ref()) {
+            let Some(converted) = stack_service(
+                spec,
+                &network_names,
+                options.include_sensitive,
+                options.pin_digests,
+            ) else {
+                continue;
+            };
+
+            record_users(
+                converted.networks,
+                &converted.stack,
+                &mut stack.networks,
+                &mut network_users,
+            );
+            record_users(
+                converted.volumes,
+                &converted.stack,
+                &mut stack.volumes,
+                &mut volume_users,
+            );
+
+            // Secret data cannot be read back and configs would need a file of
+            // their own, so both refer to the objects already in the Swarm
+            for secret in converted.service.secrets.iter().flatten() {
+                stack
+                    .secrets
+                    .insert(secret.source.clone(), external_definition());
+            }
+            for config in converted.service.configs.iter().flatten() {
+                stack
+                    .configs
+                    .insert(config.source.clone(), external_definition());
+            }
+
+            stack.services.insert(converted.name, converted.service);
+        }
+
+        self.resolve_networks(&mut stack.networks, &network_users, options.external)
+            .await?;
+        self.resolve_volumes(&mut stack.volumes, &volume_users, options.external)
+            .await?;
+
+        Ok(stack)
+    }
+
     async fn process_single_container(
         docker: Docker,
         container: ContainerSummary,
         include_sensitive_vars: bool,
-    ) -> Result<(String, Service, Vec<String>, Vec<String>)> {
+        resolver: ImageResolver,
+    ) -> Result<(String, Service, Vec<String>, Vec<String>, ContainerRelations)> {
         let container_id = container.id.clone().ok_or_else(|| {
             AutoComposeError::ContainerInspection("Container ID is missing".to_string())
         })?;
@@ -217,7 +583,112 @@ impl DockerProcessor {
             .inspect_container(&container_id, None::<InspectContainerOptions>)
             .await?;
 
-        Self::extract_service_from_inspect(inspect, container, include_sensitive_vars)
+        let mut relations = Self::extract_relations(&inspect);
+        let image_id = inspect.image.clone().unwrap_or_default();
+        let (service_name, mut service, networks, volumes) =
+            Self::extract_service_from_inspect(inspect, container, include_sensitive_vars)?;
+        relations.service = service_name.clone();
+
+        // Containers created from an image ID, or whose tag has since moved,
+        // only carry the ID; look up a readable reference instead
+        let short_id = service.image.len() >= 12
+            && image_id.trim_start_matches("sha256:").starts_with(&service.image);
+        let unresolved = is_image_id(&service.image) || short_id;
+
+        let lookup = if image_id.is_empty() { service.image.clone() } else { image_id };
+        let Some(info) = resolver.info(&lookup).await else {
+            return Ok((service_name, service, networks, volumes, relations));
+        };
+
+        service.platform = info.platform.clone();
+
+        if !resolver.keep_image_defaults {
+            prune_image_defaults(&mut service, &info.defaults);
+        }
+
+        if unresolved {
+            if let Some(reference) = info.pick(resolver.preference) {
+                service.image = reference;
+            }
+        }
+
+        if resolver.pin_digests {
+            match pin_image_digest(&service.image, &info.digests) {
+                Some(pinned) => service.image = pinned,
+                None => eprintln!(
+                    "Warning: not pinning {} for service {}: the image has no registry digest \
+                     (built locally or never pushed)",
+                    service.image, service_name
+                ),
+            }
+        }
+
+        Ok((service_name, service, networks, volumes, relations))
+    }
+
+    fn extract_relations(inspect: &ContainerInspectResponse) -> ContainerRelations {
+        let config = inspect.config.as_ref();
+        let host_config = inspect.host_config.as_ref();
+
+        let mut identifiers: Vec<String> = inspect.name.iter().cloned().collect();
+        identifiers.extend(config.and_then(|c| c.hostname.clone()));
+        let networks = inspect.network_settings.as_ref().and_then(|n| n.networks.as_ref());
+        if let Some(networks) = networks {
+            for endpoint in networks.values() {
+                identifiers.extend(endpoint.aliases.iter().flatten().cloned());
+                identifiers.extend(endpoint.dns_names.iter().flatten().cloned());
+            }
+        }
+
+        ContainerRelations {
+            service: String::new(),
+            id: inspect.id.clone().unwrap_or_default(),
+            identifiers,
+            network_container: host_config
+                .and_then(|h| h.network_mode.as_deref())
+                .and_then(|mode| mode.strip_prefix("container:"))
+                .map(|target| target.to_string()),
+            volumes_from: host_config
+                .and_then(|h| h.volumes_from.clone())
+                .unwrap_or_default(),
+            links: host_config.and_then(|h| h.links.clone()).unwrap_or_default(),
+            env_values: config
+                .and_then(|c| c.env.as_ref())
+                .map(|env| {
+                    env.iter()
+                        .filter_map(|e| e.split_once('=').map(|(_, value)| value.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            started_at: inspect.state.as_ref().and_then(|s| s.started_at.clone()),
+            completed: Self::is_completed_job(inspect),
+            // The container config already includes the image's healthcheck
+            healthcheck: config
+                .and_then(|c| c.healthcheck.as_ref())
+                .and_then(|hc| hc.test.as_ref())
+                .and_then(|test| test.first())
+                .is_some_and(|kind| kind != "NONE"),
+        }
+    }
+
+    /// A one-shot job: exited cleanly and not brought back by a restart policy.
+    fn is_completed_job(inspect: &ContainerInspectResponse) -> bool {
+        let exited_cleanly = inspect.state.as_ref().is_some_and(|state| {
+            state.status == Some(ContainerStateStatusEnum::EXITED) && state.exit_code == Some(0)
+        });
+        let restarts = inspect
+            .host_config
+            .as_ref()
+            .and_then(|h| h.restart_policy.as_ref())
+            .and_then(|p| p.name)
+            .is_some_and(|name| {
+                matches!(
+                    name,
+                    RestartPolicyNameEnum::ALWAYS | RestartPolicyNameEnum::UNLESS_STOPPED
+                )
+            });
+
+        exited_cleanly && !restarts
     }
 
     fn extract_service_from_inspect(
@@ -242,11 +713,13 @@ impl DockerProcessor {
 
         let image = config.image.unwrap_or_default();
 
-        let container_name = container
+        let raw_name = container
             .names
             .unwrap_or_default()
             .first()
-            .map(|name| sanitize_service_name(name));
+            .map(|name| name.trim_start_matches('/').to_string());
+        let container_name = raw_name.as_deref().map(sanitize_service_name);
+        let container_id = container.id.unwrap_or_default();
 
         let hostname = config.hostname;
 
@@ -306,18 +779,35 @@ impl DockerProcessor {
             }
         });
 
-        let volumes = host_config.binds.clone();
-        let mut volume_defs = Vec::new();
-        if let Some(binds) = &host_config.binds {
-            for bind in binds {
-                let parts: Vec<&str> = bind.split(':').collect();
-                if parts.len() >= 2 && !parts[0].starts_with('/') {
-                    volume_defs.push(parts[0].to_string());
-                }
-            }
-        }
+        let published: HashSet<&str> = network_settings
+            .ports
+            .iter()
+            .flatten()
+            .filter(|(_, bindings)| bindings.as_ref().is_some_and(|b| !b.is_empty()))
+            .map(|(port, _)| port.as_str())
+            .collect();
+        let expose = unpublished_ports(
+            config
+                .exposed_ports
+                .iter()
+                .flat_map(|p| p.keys())
+                .map(|p| p.as_str()),
+            &published,
+        );
 
-        let (networks, network_names) = Self::extract_networks(&network_settings, &host_config);
+        let (volumes, tmpfs, volume_defs) =
+            Self::extract_mounts(inspect.mounts.as_deref().unwrap_or_default(), &host_config);
+
+        let compose = config.labels.as_ref().and_then(ComposeLabels::from_labels);
+        // Compose aliases every container with its service name
+        let own_names: Vec<&str> = raw_name
+            .iter()
+            .chain(container_name.iter())
+            .map(|n| n.as_str())
+            .chain(compose.as_ref().map(|c| c.service.as_str()))
+            .collect();
+        let (networks, network_names) =
+            Self::extract_networks(&network_settings, &host_config, &container_id, &own_names);
 
         let dns = if host_config.dns.as_ref().is_none_or(|d| d.is_empty()) {
             None
@@ -345,7 +835,11 @@ impl DockerProcessor {
             host_config.extra_hosts.clone()
         };
 
-        let network_mode = host_config.network_mode.clone();
+        // Any other mode names a network the container is attached to, which
+        // `networks` already lists
+        let network_mode = host_config.network_mode.clone().filter(|mode| {
+            matches!(mode.as_str(), "default" | "bridge") || is_namespace_mode(mode)
+        });
 
         let cap_add = if host_config.cap_add.as_ref().is_none_or(|c| c.is_empty()) {
             None
@@ -390,13 +884,7 @@ impl DockerProcessor {
 
         let deploy = Self::extract_deploy_config(&host_config);
 
-        let healthcheck = config.healthcheck.as_ref().map(|hc| HealthCheck {
-            test: hc.test.clone().unwrap_or_default(),
-            interval: hc.interval.map(normalize_duration_from_ns),
-            timeout: hc.timeout.map(normalize_duration_from_ns),
-            retries: hc.retries,
-            start_period: hc.start_period.map(normalize_duration_from_ns),
-        });
+        let healthcheck = config.healthcheck.as_ref().map(Self::health_check);
 
         let labels = config.labels.and_then(filter_system_labels);
 
@@ -405,24 +893,7 @@ impl DockerProcessor {
             options: log_config.config.clone(),
         });
 
-        let devices = host_config.devices.as_ref().and_then(|devs| {
-            let device_list: Vec<String> = devs
-                .iter()
-                .map(|d| {
-                    format!(
-                        "{}:{}:{}",
-                        d.path_on_host.clone().unwrap_or_default(),
-                        d.path_in_container.clone().unwrap_or_default(),
-                        d.cgroup_permissions.clone().unwrap_or_default()
-                    )
-                })
-                .collect();
-            if device_list.is_empty() {
-                None
-            } else {
-                Some(device_list)
-            }
-        });
+        let devices = Self::extract_devices(&host_config);
 
         let ulimits = host_config.ulimits.as_ref().and_then(|ulimit_list| {
             let ulimit_map: HashMap<String, UlimitConfig> = ulimit_list
@@ -454,6 +925,60 @@ impl DockerProcessor {
         let tty = config.tty;
         let stdin_open = config.open_stdin;
 
+        // Namespace, cgroup and runtime settings; defaults are pruned later
+        let mode = |mode: &Option<String>| mode.clone().filter(|m| !m.is_empty());
+        let pid = mode(&host_config.pid_mode);
+        let ipc = mode(&host_config.ipc_mode);
+        let uts = mode(&host_config.uts_mode);
+        let userns_mode = mode(&host_config.userns_mode);
+        let cgroup = host_config
+            .cgroupns_mode
+            .map(|m| m.to_string())
+            .filter(|m| !m.is_empty());
+        let cgroup_parent = mode(&host_config.cgroup_parent);
+        let isolation = host_config
+            .isolation
+            .map(|i| i.to_string())
+            .filter(|i| !i.is_empty() && i != "default");
+        let runtime = mode(&host_config.runtime);
+
+        // Resource controls the engine leaves at zero when they are unset
+        let positive = |value: Option<i64>| value.filter(|v| *v > 0);
+        let cpu_shares = positive(host_config.cpu_shares);
+        let cpu_period = positive(host_config.cpu_period);
+        let cpu_quota = positive(host_config.cpu_quota);
+        let cpuset = mode(&host_config.cpuset_cpus);
+        let mem_swappiness = host_config.memory_swappiness.filter(|s| *s >= 0);
+        let memswap_limit = memswap_limit(
+            host_config.memory.unwrap_or_default(),
+            host_config.memory_swap.unwrap_or_default(),
+        );
+        let pids_limit = positive(host_config.pids_limit);
+        let oom_kill_disable = host_config.oom_kill_disable.filter(|d| *d);
+        let oom_score_adj = host_config.oom_score_adj.filter(|a| *a != 0);
+        let shm_size = positive(host_config.shm_size).map(format_size_bytes);
+        let blkio_config = Self::extract_blkio_config(&host_config);
+
+        let read_only = host_config.readonly_rootfs.filter(|r| *r);
+        let stop_signal = config.stop_signal.filter(|s| !s.is_empty());
+        let stop_grace_period = config
+            .stop_timeout
+            .filter(|t| *t >= 0)
+            .map(format_duration_secs);
+        let group_add = host_config.group_add.clone().filter(|g| !g.is_empty());
+        let domainname = config.domainname.filter(|d| !d.is_empty());
+        // Only the container config holds the MAC address asked for at creation
+        let mac_address = config.mac_address.filter(|mac| !mac.is_empty());
+        let storage_opt = host_config.storage_opt.clone().filter(|o| !o.is_empty());
+        let device_cgroup_rules = host_config
+            .device_cgroup_rules
+            .clone()
+            .filter(|r| !r.is_empty());
+        let annotations = host_config
+            .annotations
+            .clone()
+            .and_then(filter_system_annotations);
+
         let user = config.user.filter(|u| !u.is_empty());
         let working_dir = config.working_dir.filter(|w| !w.is_empty());
         let entrypoint = config.entrypoint.map(|e| e.into_iter().collect());
@@ -461,11 +986,14 @@ impl DockerProcessor {
 
         let service = Service {
             image,
+            platform: None, // Read from the image once it has been inspected
             container_name: container_name.clone(),
             hostname,
             environment,
             ports,
+            expose,
             volumes,
+            tmpfs,
             networks,
             network_mode,
             dns,
@@ -475,6 +1003,25 @@ impl DockerProcessor {
             cap_add,
             cap_drop,
             security_opt,
+            pid,
+            ipc,
+            uts,
+            userns_mode,
+            cgroup,
+            cgroup_parent,
+            isolation,
+            runtime,
+            cpu_shares,
+            cpu_period,
+            cpu_quota,
+            cpuset,
+            mem_swappiness,
+            memswap_limit,
+            pids_limit,
+            oom_kill_disable,
+            oom_score_adj,
+            shm_size,
+            blkio_config,
             deploy,
             healthcheck,
             labels,
@@ -490,23 +1037,295 @@ impl DockerProcessor {
             privileged,
             tty,
             stdin_open,
-            depends_on: None, // Docker doesn't provide dependency info
+            read_only,
+            stop_signal,
+            stop_grace_period,
+            group_add,
+            domainname,
+            mac_address,
+            storage_opt,
+            device_cgroup_rules,
+            annotations,
+            secrets: None,
+            configs: None,
+            depends_on: None, // Inferred once every container has been inspected
+            x_podman: None,
+            compose,
         };
 
         let service_name = container_name.unwrap_or_else(|| "service".to_string());
         Ok((service_name, service, network_names, volume_defs))
     }
 
+    pub(crate) fn health_check(hc: &HealthConfig) -> HealthCheck {
+        HealthCheck {
+            test: hc.test.clone().unwrap_or_default(),
+            interval: hc.interval.map(normalize_duration_from_ns),
+            timeout: hc.timeout.map(normalize_duration_from_ns),
+            retries: hc.retries,
+            start_period: hc.start_period.map(normalize_duration_from_ns),
+        }
+    }
+
+    fn extract_mounts(
+        mounts: &[MountPoint],
+        host_config: &bollard::models::HostConfig,
+    ) -> (Option<Vec<ServiceVolume>>, Option<Vec<String>>, Vec<String>) {
+        let mut volumes = Vec::new();
+        let mut tmpfs = Vec::new();
+        let mut volume_defs = Vec::new();
+
+        for mount in mounts {
+            let target = match mount.destination.as_deref() {
+                Some(target) if !target.is_empty() => target,
+                _ => continue,
+            };
+
+            // Mounts created with --mount keep their options in HostConfig.Mounts
+            let spec = host_config
+                .mounts
+                .as_ref()
+                .and_then(|specs| specs.iter().find(|m| m.target.as_deref() == Some(target)));
+
+            let mode_flags: Vec<&str> = mount
+                .mode
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .filter(|flag| !flag.is_empty())
+                .collect();
+
+            let read_only = mount.rw == Some(false);
+            let mut options = Vec::new();
+            if read_only {
+                options.push("ro".to_string());
+            }
+
+            match mount.typ {
+                Some(MountPointTypeEnum::BIND) => {
+                    let source = match mount.source.as_deref() {
+                        Some(source) if !source.is_empty() => source,
+                        _ => continue,
+                    };
+
+                    // rprivate is the kernel default and is omitted
+                    let propagation = mount
+                        .propagation
+                        .as_deref()
+                        .filter(|p| !p.is_empty() && *p != "rprivate");
+                    let selinux = mode_flags
+                        .iter()
+                        .find(|flag| **flag == "z" || **flag == "Z")
+                        .map(|flag| flag.to_string());
+
+                    // Unlike -v, a --mount bind fails when the host path is missing
+                    let create_host_path = spec
+                        .filter(|m| m.typ == Some(MountTypeEnum::BIND))
+                        .map(|m| {
+                            m.bind_options
+                                .as_ref()
+                                .and_then(|opts| opts.create_mountpoint)
+                                .unwrap_or(false)
+                        });
+
+                    if create_host_path == Some(false) {
+                        volumes.push(ServiceVolume::Long(VolumeMount {
+                            mount_type: "bind".to_string(),
+                            source: Some(source.to_string()),
+                            target: target.to_string(),
+                            read_only: read_only.then_some(true),
+                            bind: Some(BindOptions {
+                                propagation: propagation.map(|p| p.to_string()),
+                                create_host_path: Some(false),
+                                selinux,
+                            }),
+                            volume: None,
+                            tmpfs: None,
+                        }));
+                        continue;
+                    }
+
+                    options.extend(propagation.map(|p| p.to_string()));
+                    options.extend(selinux);
+                    volumes.push(ServiceVolume::Short(Self::format_volume_entry(
+                        source, target, &options,
+                    )));
+                }
+                Some(MountPointTypeEnum::VOLUME) => {
+                    let name = mount.name.as_deref().unwrap_or_default();
+
+                    // Anonymous volumes only carry their container path
+                    if name.is_empty() || is_anonymous_volume(name) {
+                        volumes.push(ServiceVolume::Short(target.to_string()));
+                        continue;
+                    }
+
+                    if !volume_defs.iter().any(|v| v == name) {
+                        volume_defs.push(name.to_string());
+                    }
+
+                    let volume_options = spec.and_then(|m| m.volume_options.as_ref());
+                    let no_copy = mode_flags.contains(&"nocopy")
+                        || volume_options.and_then(|opts| opts.no_copy).unwrap_or(false);
+                    let subpath = volume_options
+                        .and_then(|opts| opts.subpath.clone())
+                        .filter(|p| !p.is_empty());
+
+                    if subpath.is_some() {
+                        volumes.push(ServiceVolume::Long(VolumeMount {
+                            mount_type: "volume".to_string(),
+                            source: Some(name.to_string()),
+                            target: target.to_string(),
+                            read_only: read_only.then_some(true),
+                            bind: None,
+                            volume: Some(VolumeOptions {
+                                nocopy: no_copy.then_some(true),
+                                subpath,
+                            }),
+                            tmpfs: None,
+                        }));
+                        continue;
+                    }
+
+                    if no_copy {
+                        options.push("nocopy".to_string());
+                    }
+                    volumes.push(ServiceVolume::Short(Self::format_volume_entry(
+                        name, target, &options,
+                    )));
+                }
+                Some(MountPointTypeEnum::TMPFS) => {
+                    let tmpfs_options = spec.and_then(|m| m.tmpfs_options.as_ref());
+                    let size = tmpfs_options
+                        .and_then(|opts| opts.size_bytes)
+                        .filter(|s| *s > 0);
+                    let mode = tmpfs_options
+                        .and_then(|opts| opts.mode)
+                        .filter(|m| *m > 0)
+                        .and_then(|m| format!("{:o}", m).parse::<u32>().ok());
+
+                    if size.is_none() && mode.is_none() && !read_only {
+                        tmpfs.push(target.to_string());
+                        continue;
+                    }
+
+                    volumes.push(ServiceVolume::Long(VolumeMount {
+                        mount_type: "tmpfs".to_string(),
+                        source: None,
+                        target: target.to_string(),
+                        read_only: read_only.then_some(true),
+                        bind: None,
+                        volume: None,
+                        tmpfs: Some(TmpfsOptions { size, mode }),
+                    }));
+                }
+                // Named pipes, image and cluster mounts have no compose equivalent
+                _ => {}
+            }
+        }
+
+        let volumes = if volumes.is_empty() {
+            None
+        } else {
+            Some(volumes)
+        };
+        // --tmpfs mounts are only listed in HostConfig.Tmpfs
+        let mut tmpfs_flags: Vec<_> = host_config
+            .tmpfs
+            .iter()
+            .flatten()
+            .filter(|(target, _)| !mounts.iter().any(|m| m.destination.as_ref() == Some(target)))
+            .map(|(target, options)| tmpfs_entry(target, options))
+            .collect();
+        tmpfs_flags.sort();
+        tmpfs.extend(tmpfs_flags);
+
+        let tmpfs = if tmpfs.is_empty() { None } else { Some(tmpfs) };
+
+        (volumes, tmpfs, volume_defs)
+    }
+
+    fn format_volume_entry(source: &str, target: &str, options: &[String]) -> String {
+        if options.is_empty() {
+            format!("{}:{}", source, target)
+        } else {
+            format!("{}:{}:{}", source, target, options.join(","))
+        }
+    }
+
+    fn is_predefined_network(name: &str) -> bool {
+        matches!(name, "bridge" | "host" | "none")
+    }
+
+    fn network_definition(network: Network) -> NetworkDefinition {
+        let ipam = network.ipam.and_then(|ipam| {
+            let config: Vec<IpamPool> = ipam
+                .config
+                .unwrap_or_default()
+                .into_iter()
+                .map(|pool| IpamPool {
+                    subnet: pool.subnet.filter(|s| !s.is_empty()),
+                    gateway: pool.gateway.filter(|g| !g.is_empty()),
+                    ip_range: pool.ip_range.filter(|r| !r.is_empty()),
+                    aux_addresses: pool.auxiliary_addresses.filter(|a| !a.is_empty()),
+                })
+                .filter(|pool| pool.subnet.is_some())
+                .collect();
+            let definition = IpamDefinition {
+                driver: ipam.driver.filter(|d| !d.is_empty() && d != "default"),
+                config: if config.is_empty() { None } else { Some(config) },
+                options: ipam.options.filter(|o| !o.is_empty()),
+            };
+
+            if definition.driver.is_none()
+                && definition.config.is_none()
+                && definition.options.is_none()
+            {
+                None
+            } else {
+                Some(definition)
+            }
+        });
+
+        NetworkDefinition {
+            driver: network.driver.filter(|d| !d.is_empty()),
+            driver_opts: network.options.filter(|o| !o.is_empty()),
+            ipam,
+            internal: network.internal.filter(|i| *i),
+            attachable: network.attachable.filter(|a| *a),
+            enable_ipv6: network.enable_ipv6.filter(|e| *e),
+            labels: network.labels.and_then(filter_system_labels),
+            name: network.name,
+            ..Default::default()
+        }
+    }
+
+    fn volume_definition(volume: Volume) -> VolumeDefinition {
+        VolumeDefinition {
+            driver: Some(volume.driver).filter(|d| !d.is_empty()),
+            driver_opts: Some(volume.options).filter(|o| !o.is_empty()),
+            labels: filter_system_labels(volume.labels),
+            name: Some(volume.name),
+            ..Default::default()
+        }
+    }
+
     fn extract_networks(
         network_settings: &bollard::models::NetworkSettings,
         host_config: &bollard::models::HostConfig,
+        container_id: &str,
+        own_names: &[&str],
     ) -> (Option<ServiceNetworks>, Vec<String>) {
-        if let Some(network_mode) = &host_config.network_mode {
-            if network_mode != "default" && network_mode != "bridge" {
-                return (None, vec![]);
-            }
+        if host_config
+            .network_mode
+            .as_deref()
+            .is_some_and(is_namespace_mode)
+        {
+            return (None, vec![]);
         }
 
+        let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.is_empty());
+
         if let Some(networks_map) = &network_settings.networks {
             let mut network_configs = HashMap::new();
             let mut network_names = Vec::new();
@@ -514,25 +1333,30 @@ impl DockerProcessor {
             for (net_name, net_info) in networks_map {
                 network_names.push(net_name.clone());
 
-                if let Some(ip) = &net_info.ip_address {
-                    if !ip.is_empty() {
-                        let mut config = NetworkConfig {
-                            ipv4_address: Some(ip.clone()),
-                            ipv6_address: None,
-                        };
-
-                        if let Some(ipv6) = &net_info.global_ipv6_address {
-                            if !ipv6.is_empty() {
-                                config.ipv6_address = Some(ipv6.clone());
-                            }
-                        }
+                // Only the addresses asked for at creation are in IPAMConfig; the
+                // others were assigned by the engine and must not become static
+                let ipam = net_info.ipam_config.as_ref();
+                let config = NetworkConfig {
+                    aliases: net_info
+                        .aliases
+                        .clone()
+                        .and_then(|a| filter_endpoint_aliases(a, container_id, own_names)),
+                    ipv4_address: non_empty(&ipam.and_then(|ipam| ipam.ipv4_address.clone())),
+                    ipv6_address: non_empty(&ipam.and_then(|ipam| ipam.ipv6_address.clone())),
+                    link_local_ips: ipam
+                        .and_then(|ipam| ipam.link_local_ips.clone())
+                        .filter(|ips| !ips.is_empty()),
+                    // The endpoint reports the MAC in use, whether requested or
+                    // assigned; a requested one is kept at the service level
+                    mac_address: None,
+                    driver_opts: net_info.driver_opts.clone().filter(|o| !o.is_empty()),
+                    gw_priority: net_info.gw_priority.filter(|p| *p != 0),
+                };
 
-                        network_configs.insert(net_name.clone(), config);
-                    }
-                }
+                network_configs.insert(net_name.clone(), config);
             }
 
-            let networks = if network_configs.is_empty() {
+            let networks = if network_configs.values().all(NetworkConfig::is_empty) {
                 if network_names.len() == 1 && network_names[0] == "bridge" {
                     None
                 } else {
@@ -549,52 +1373,568 @@ impl DockerProcessor {
     }
 
     fn extract_deploy_config(host_config: &bollard::models::HostConfig) -> Option<Deploy> {
-        let mut has_resources = false;
-        let mut limits = HashMap::new();
-
-        if let Some(memory) = host_config.memory {
-            if memory > 0 {
-                let memory_mb = memory / (1024 * 1024);
-                limits.insert("memory".to_string(), format!("{}M", memory_mb));
-                has_resources = true;
-            }
-        }
+        let positive = |value: Option<i64>| value.filter(|v| *v > 0);
+        let limits = ResourceLimits {
+            cpus: positive(host_config.nano_cpus).map(|n| format!("{:.2}", n as f64 / 1e9)),
+            memory: positive(host_config.memory).map(format_size_bytes),
+        };
+        let reservations = ResourceReservations {
+            memory: positive(host_config.memory_reservation).map(format_size_bytes),
+            devices: Self::extract_device_reservations(host_config),
+            ..Default::default()
+        };
 
-        if let (Some(cpu_quota), Some(cpu_period)) = (host_config.cpu_quota, host_config.cpu_period)
-        {
-            if cpu_quota > 0 && cpu_period > 0 {
-                let cpus = cpu_quota as f64 / cpu_period as f64;
-                limits.insert("cpus".to_string(), format!("{:.2}", cpus));
-                has_resources = true;
-            }
-        }
+        Resources::new(limits, reservations).map(|resources| Deploy {
+            resources: Some(resources),
+            ..Default::default()
+        })
+    }
 
-        let resources = if has_resources {
-            Some(Resources {
-                limits: Some(ResourceLimits {
-                    memory: limits.get("memory").cloned(),
-                    cpus: limits.get("cpus").cloned(),
-                }),
+    fn extract_devices(host_config: &bollard::models::HostConfig) -> Option<Vec<String>> {
+        let device_list: Vec<String> = host_config
+            .devices
+            .iter()
+            .flatten()
+            .map(|d| {
+                format!(
+                    "{}:{}:{}",
+                    d.path_on_host.clone().unwrap_or_default(),
+                    d.path_in_container.clone().unwrap_or_default(),
+                    d.cgroup_permissions.clone().unwrap_or_default()
+                )
             })
-        } else {
+            // `--device vendor.com/class=name` becomes a request for the CDI driver
+            .chain(
+                host_config
+                    .device_requests
+                    .iter()
+                    .flatten()
+                    .filter(|request| request.driver.as_deref() == Some("cdi"))
+                    .flat_map(|request| request.device_ids.iter().flatten().cloned()),
+            )
+            .collect();
+        if device_list.is_empty() {
             None
+        } else {
+            Some(device_list)
+        }
+    }
+
+    /// Device requests of `--gpus` and the like; CDI requests are listed in `devices` instead.
+    fn extract_device_reservations(
+        host_config: &bollard::models::HostConfig,
+    ) -> Option<Vec<DeviceReservation>> {
+        let devices: Vec<DeviceReservation> = host_config
+            .device_requests
+            .iter()
+            .flatten()
+            .filter(|request| request.driver.as_deref() != Some("cdi"))
+            .map(|request| {
+                // The engine accepts alternative capability sets, compose a single one
+                let capability_sets = request.capabilities.as_deref().unwrap_or_default();
+                if capability_sets.len() > 1 {
+                    eprintln!("Warning: Only the first capability set of a device request is kept");
+                }
+                let count = match request.count {
+                    Some(-1) => Some(DeviceCount::All("all".to_string())),
+                    Some(count) if count > 0 => Some(DeviceCount::Count(count as u64)),
+                    _ => None,
+                };
+                DeviceReservation {
+                    driver: request.driver.clone().filter(|d| !d.is_empty()),
+                    count,
+                    device_ids: request.device_ids.clone().filter(|ids| !ids.is_empty()),
+                    capabilities: capability_sets.first().cloned().unwrap_or_default(),
+                    options: request.options.clone().filter(|o| !o.is_empty()),
+                }
+            })
+            .collect();
+        (!devices.is_empty()).then_some(devices)
+    }
+
+    fn extract_blkio_config(host_config: &bollard::models::HostConfig) -> Option<BlkioConfig> {
+        let throttles = |devices: &Option<Vec<ThrottleDevice>>| {
+            let throttles: Vec<_> = devices
+                .iter()
+                .flatten()
+                .filter_map(|device| {
+                    Some(BlkioThrottle {
+                        path: device.path.clone().filter(|p| !p.is_empty())?,
+                        rate: device.rate.filter(|r| *r > 0)? as u64,
+                    })
+                })
+                .collect();
+            (!throttles.is_empty()).then_some(throttles)
         };
+        let weight_device: Vec<_> = host_config
+            .blkio_weight_device
+            .iter()
+            .flatten()
+            .filter_map(|device| {
+                Some(BlkioWeightDevice {
+                    path: device.path.clone().filter(|p| !p.is_empty())?,
+                    weight: device.weight.filter(|w| *w > 0)? as u16,
+                })
+            })
+            .collect();
 
-        let placement = host_config
-            .cpuset_cpus
-            .as_ref()
-            .filter(|cpus| !cpus.is_empty())
-            .map(|cpus| Placement {
-                constraints: Some(vec![format!("node.labels.cpus == {}", cpus)]),
-            });
+        let blkio = BlkioConfig {
+            weight: host_config.blkio_weight.filter(|w| *w > 0),
+            weight_device: (!weight_device.is_empty()).then_some(weight_device),
+            device_read_bps: throttles(&host_config.blkio_device_read_bps),
+            device_write_bps: throttles(&host_config.blkio_device_write_bps),
+            device_read_iops: throttles(&host_config.blkio_device_read_iops),
+            device_write_iops: throttles(&host_config.blkio_device_write_iops),
+        };
+        (!blkio.is_empty()).then_some(blkio)
+    }
+}
 
-        if resources.is_some() || placement.is_some() {
-            Some(Deploy {
-                resources,
-                placement,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inspect_fixture(
+        mounts: serde_json::Value,
+        host_mounts: serde_json::Value,
+    ) -> ContainerInspectResponse {
+        serde_json::from_value(serde_json::json!({
+            "Id": "abc123",
+            "Name": "/web",
+            "Config": { "Image": "nginx:1.25" },
+            "HostConfig": { "Mounts": host_mounts },
+            "NetworkSettings": {},
+            "Mounts": mounts
+        }))
+        .unwrap()
+    }
+
+    fn summary_fixture() -> ContainerSummary {
+        ContainerSummary {
+            id: Some("abc123".to_string()),
+            names: Some(vec!["/web".to_string()]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_extract_mounts_from_inspect() {
+        let anonymous = "a".repeat(64);
+        let inspect = inspect_fixture(
+            serde_json::json!([
+                { "Type": "bind", "Source": "/srv/conf", "Destination": "/etc/nginx",
+                  "Mode": "ro,Z", "RW": false, "Propagation": "rshared" },
+                { "Type": "volume", "Name": "web-data", "Source": "/var/lib/docker/volumes/web-data/_data",
+                  "Destination": "/data", "Driver": "local", "Mode": "", "RW": true, "Propagation": "" },
+                { "Type": "volume", "Name": anonymous, "Destination": "/cache", "RW": true },
+                { "Type": "tmpfs", "Source": "", "Destination": "/run", "Mode": "", "RW": true }
+            ]),
+            serde_json::json!([
+                { "Type": "volume", "Source": "web-data", "Target": "/data",
+                  "VolumeOptions": { "NoCopy": true } },
+                { "Type": "tmpfs", "Target": "/run",
+                  "TmpfsOptions": { "SizeBytes": 67108864, "Mode": 1023 } }
+            ]),
+        );
+
+        let (_, service, _, volume_defs) =
+            DockerProcessor::extract_service_from_inspect(inspect, summary_fixture(), false).unwrap();
+
+        let volumes = service.volumes.unwrap();
+        let short: Vec<&str> = volumes
+            .iter()
+            .filter_map(|v| match v {
+                ServiceVolume::Short(spec) => Some(spec.as_str()),
+                ServiceVolume::Long(_) => None,
             })
-        } else {
-            None
+            .collect();
+        assert!(short.contains(&"/srv/conf:/etc/nginx:ro,rshared,Z"));
+        assert!(short.contains(&"web-data:/data:nocopy"));
+        assert!(short.contains(&"/cache"));
+        assert_eq!(volumes.len(), 4);
+        assert!(service.tmpfs.is_none());
+        assert_eq!(volume_defs, vec!["web-data".to_string()]);
+
+        let yaml = serde_yaml::to_string(&volumes).unwrap();
+        assert!(yaml.contains("type: tmpfs"));
+        assert!(yaml.contains("size: 67108864"));
+        assert!(yaml.contains("mode: 1777"));
+    }
+
+    #[test]
+    fn test_extract_mounts_uses_long_syntax_for_mount_binds() {
+        let inspect = inspect_fixture(
+            serde_json::json!([
+                { "Type": "bind", "Source": "/srv/app", "Destination": "/app",
+                  "Mode": "", "RW": true, "Propagation": "rprivate" },
+                { "Type": "tmpfs", "Source": "", "Destination": "/tmp", "Mode": "", "RW": true }
+            ]),
+            serde_json::json!([
+                { "Type": "bind", "Source": "/srv/app", "Target": "/app" },
+                { "Type": "tmpfs", "Target": "/tmp" }
+            ]),
+        );
+
+        let (_, service, _, _) =
+            DockerProcessor::extract_service_from_inspect(inspect, summary_fixture(), false).unwrap();
+
+        let volumes = service.volumes.unwrap();
+        match &volumes[0] {
+            ServiceVolume::Long(mount) => {
+                assert_eq!(mount.mount_type, "bind");
+                assert_eq!(mount.source.as_deref(), Some("/srv/app"));
+                assert_eq!(
+                    mount.bind.as_ref().and_then(|b| b.create_host_path),
+                    Some(false)
+                );
+            }
+            other => panic!("expected long syntax, got {:?}", other),
         }
+        assert_eq!(service.tmpfs, Some(vec!["/tmp".to_string()]));
+    }
+
+    #[test]
+    fn test_volume_definition_from_inspect() {
+        let volume: Volume = serde_json::from_value(serde_json::json!({
+            "Name": "app_media",
+            "Driver": "local",
+            "Mountpoint": "/var/lib/docker/volumes/app_media/_data",
+            "Labels": {
+                "com.docker.compose.project": "app",
+                "backup": "daily"
+            },
+            "Scope": "local",
+            "Options": {
+                "type": "nfs",
+                "o": "addr=10.0.0.5,rw",
+                "device": ":/exports/media"
+            }
+        }))
+        .unwrap();
+
+        let definition = DockerProcessor::volume_definition(volume);
+        assert_eq!(definition.driver.as_deref(), Some("local"));
+        assert_eq!(definition.name.as_deref(), Some("app_media"));
+        let driver_opts = definition.driver_opts.unwrap();
+        assert_eq!(driver_opts.get("type").map(String::as_str), Some("nfs"));
+        assert_eq!(driver_opts.get("device").map(String::as_str), Some(":/exports/media"));
+        let labels = definition.labels.unwrap();
+        assert_eq!(labels.len(), 1);
+        assert!(labels.contains_key("backup"));
+    }
+
+    #[test]
+    fn test_network_definition_from_inspect() {
+        let network: Network = serde_json::from_value(serde_json::json!({
+            "Name": "backend",
+            "Driver": "bridge",
+            "EnableIPv6": true,
+            "IPAM": {
+                "Driver": "default",
+                "Options": {},
+                "Config": [
+                    { "Subnet": "172.28.0.0/16", "Gateway": "172.28.0.1", "IPRange": "172.28.5.0/24" },
+                    { "Subnet": "fd00:28::/64" }
+                ]
+            },
+            "Internal": true,
+            "Attachable": false,
+            "Options": { "com.docker.network.bridge.name": "br-backend" },
+            "Labels": { "com.docker.compose.network": "backend", "team": "payments" }
+        }))
+        .unwrap();
+
+        let definition = DockerProcessor::network_definition(network);
+        assert_eq!(definition.driver.as_deref(), Some("bridge"));
+        assert_eq!(definition.name.as_deref(), Some("backend"));
+        assert_eq!(definition.internal, Some(true));
+        assert_eq!(definition.attachable, None);
+        assert_eq!(definition.enable_ipv6, Some(true));
+        assert_eq!(definition.labels.unwrap().len(), 1);
+        assert_eq!(definition.driver_opts.unwrap().len(), 1);
+
+        let ipam = definition.ipam.unwrap();
+        assert!(ipam.driver.is_none());
+        assert!(ipam.options.is_none());
+        let pools = ipam.config.unwrap();
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].subnet.as_deref(), Some("172.28.0.0/16"));
+        assert_eq!(pools[0].gateway.as_deref(), Some("172.28.0.1"));
+        assert_eq!(pools[0].ip_range.as_deref(), Some("172.28.5.0/24"));
+        assert!(pools[1].gateway.is_none());
+    }
+
+    #[test]
+    fn test_extract_mounts_without_mounts() {
+        let inspect = inspect_fixture(serde_json::json!([]), serde_json::json!([]));
+
+        let (_, service, _, volume_defs) =
+            DockerProcessor::extract_service_from_inspect(inspect, summary_fixture(), false).unwrap();
+
+        assert!(service.volumes.is_none());
+        assert!(service.tmpfs.is_none());
+        assert!(volume_defs.is_empty());
+    }
+
+    #[test]
+    fn test_extract_lifecycle_and_filesystem_settings() {
+        let inspect: ContainerInspectResponse = serde_json::from_value(serde_json::json!({
+            "Id": "abc123",
+            "Name": "/web",
+            "Config": {
+                "Image": "nginx:1.25",
+                "StopSignal": "SIGQUIT",
+                "StopTimeout": 90,
+                "Domainname": "example.internal",
+                "MacAddress": "4e:1c:7d:22:90:01"
+            },
+            "HostConfig": {
+                "ReadonlyRootfs": true,
+                "Tmpfs": { "/run": "", "/tmp": "size=64m,mode=1777" },
+                "GroupAdd": ["audio"],
+                "DeviceCgroupRules": ["c 42:* rmw"],
+                "Annotations": { "com.example.owner": "web-team" }
+            },
+            "NetworkSettings": {},
+            "Mounts": []
+        }))
+        .unwrap();
+
+        let (_, service, _, _) =
+            DockerProcessor::extract_service_from_inspect(inspect, summary_fixture(), false)
+                .unwrap();
+
+        assert_eq!(service.read_only, Some(true));
+        assert_eq!(service.stop_signal.as_deref(), Some("SIGQUIT"));
+        assert_eq!(service.stop_grace_period.as_deref(), Some("1m30s"));
+        assert_eq!(service.domainname.as_deref(), Some("example.internal"));
+        assert_eq!(service.mac_address.as_deref(), Some("4e:1c:7d:22:90:01"));
+        assert_eq!(
+            service.tmpfs,
+            Some(vec![
+                "/run".to_string(),
+                "/tmp:size=64m,mode=1777".to_string()
+            ])
+        );
+        assert_eq!(service.group_add, Some(vec!["audio".to_string()]));
+        assert_eq!(service.device_cgroup_rules.unwrap().len(), 1);
+        assert_eq!(
+            service.annotations.unwrap()["com.example.owner"],
+            "web-team"
+        );
+    }
+
+    #[test]
+    fn test_extract_networks_endpoint_settings() {
+        let network_settings: bollard::models::NetworkSettings =
+            serde_json::from_value(serde_json::json!({
+                "Networks": {
+                    "proxy": {
+                        "Aliases": ["abc123def456", "web", "frontend", "www.internal"],
+                        "IPAddress": "172.30.0.5",
+                        "MacAddress": "02:42:ac:1e:00:05",
+                        "IPAMConfig": {
+                            "IPv4Address": "172.30.0.5",
+                            "LinkLocalIPs": ["169.254.10.5"]
+                        },
+                        "DriverOpts": { "com.example.mtu": "1400" },
+                        "GwPriority": 10
+                    },
+                    "backend": {
+                        "IPAddress": "172.31.0.7",
+                        "MacAddress": "02:42:ac:1f:00:07",
+                        "IPAMConfig": null
+                    },
+                    "storage": {
+                        "IPAddress": "172.32.0.2",
+                        "MacAddress": "4e:1c:7d:22:90:01"
+                    }
+                }
+            }))
+            .unwrap();
+
+        let host_config = bollard::models::HostConfig {
+            network_mode: Some("proxy".to_string()),
+            ..Default::default()
+        };
+        let (networks, names) = DockerProcessor::extract_networks(
+            &network_settings,
+            &host_config,
+            "abc123def4567890",
+            &["web", "frontend"],
+        );
+
+        assert_eq!(names.len(), 3);
+        let Some(ServiceNetworks::Advanced(configs)) = networks else {
+            panic!("expected per-network settings");
+        };
+        let proxy = &configs["proxy"];
+        assert_eq!(proxy.aliases, Some(vec!["www.internal".to_string()]));
+        assert_eq!(proxy.ipv4_address.as_deref(), Some("172.30.0.5"));
+        assert!(proxy.mac_address.is_none());
+        assert_eq!(proxy.link_local_ips, Some(vec!["169.254.10.5".to_string()]));
+        assert_eq!(proxy.driver_opts.as_ref().unwrap()["com.example.mtu"], "1400");
+        assert_eq!(proxy.gw_priority, Some(10));
+        // Addresses the engine picked on its own are left out
+        assert!(configs["backend"].is_empty());
+        assert!(configs["storage"].is_empty());
+    }
+
+    #[test]
+    fn test_extract_service_on_user_defined_network() {
+        let inspect: ContainerInspectResponse = serde_json::from_value(serde_json::json!({
+            "Id": "abc123",
+            "Name": "/web",
+            "Config": { "Image": "nginx:1.25" },
+            "HostConfig": { "NetworkMode": "shop_default" },
+            "NetworkSettings": {
+                "Networks": {
+                    "shop_default": { "IPAddress": "172.30.0.5" }
+                }
+            },
+            "Mounts": []
+        }))
+        .unwrap();
+
+        let (_, service, network_names, _) =
+            DockerProcessor::extract_service_from_inspect(inspect, summary_fixture(), false)
+                .unwrap();
+
+        assert_eq!(network_names, vec!["shop_default".to_string()]);
+        assert!(
+            matches!(&service.networks, Some(ServiceNetworks::Simple(n)) if n == &["shop_default"])
+        );
+        assert!(service.network_mode.is_none());
+    }
+
+    #[test]
+    fn test_shared_user_defined_network_is_external() {
+        let mut networks = HashMap::new();
+        let mut users = HashMap::new();
+        for (name, project) in [("/shop-web-1", "shop"), ("/blog-web-1", "blog")] {
+            let inspect: ContainerInspectResponse = serde_json::from_value(serde_json::json!({
+                "Id": "abc123",
+                "Name": name,
+                "Config": { "Image": "nginx:1.25" },
+                "HostConfig": { "NetworkMode": "proxy" },
+                "NetworkSettings": { "Networks": { "proxy": {} } },
+                "Mounts": []
+            }))
+            .unwrap();
+            let (_, _, nets, _) =
+                DockerProcessor::extract_service_from_inspect(inspect, summary_fixture(), false)
+                    .unwrap();
+            record_users(nets, &Some(project.to_string()), &mut networks, &mut users);
+        }
+
+        assert!(networks.contains_key("proxy"));
+        // Created by one project and joined by another
+        assert!(ExternalMode::Auto.applies(Some("shop"), &users["proxy"]));
+    }
+
+    #[test]
+    fn test_extract_resource_controls() {
+        let host_config: bollard::models::HostConfig = serde_json::from_value(serde_json::json!({
+            "NanoCpus": 1500000000,
+            "Memory": 536870912,
+            "MemoryReservation": 268435456,
+            "CpusetCpus": "0-1",
+            "BlkioWeight": 300,
+            "BlkioDeviceReadBps": [{ "Path": "/dev/sda", "Rate": 1048576 }],
+            "BlkioDeviceWriteIOps": []
+        }))
+        .unwrap();
+
+        let deploy = DockerProcessor::extract_deploy_config(&host_config).unwrap();
+        let resources = deploy.resources.unwrap();
+        let limits = resources.limits.unwrap();
+        assert_eq!(limits.cpus.as_deref(), Some("1.50"));
+        assert_eq!(limits.memory.as_deref(), Some("512M"));
+        assert_eq!(
+            resources.reservations.unwrap().memory.as_deref(),
+            Some("256M")
+        );
+        // cpuset is a service field now, not a placement constraint
+        assert!(deploy.placement.is_none());
+
+        let blkio = DockerProcessor::extract_blkio_config(&host_config).unwrap();
+        assert_eq!(blkio.weight, Some(300));
+        assert_eq!(blkio.device_read_bps.unwrap()[0].rate, 1048576);
+        assert!(blkio.device_write_iops.is_none());
+    }
+
+    #[test]
+    fn test_extract_device_requests() {
+        let host_config: bollard::models::HostConfig = serde_json::from_value(serde_json::json!({
+            "Devices": [{
+                "PathOnHost": "/dev/fuse",
+                "PathInContainer": "/dev/fuse",
+                "CgroupPermissions": "rwm"
+            }],
+            "DeviceRequests": [
+                { "Driver": "", "Count": -1, "Capabilities": [["gpu"]] },
+                {
+                    "Driver": "nvidia",
+                    "Count": 0,
+                    "DeviceIDs": ["0", "3"],
+                    "Capabilities": [["gpu", "utility"]],
+                    "Options": { "visible": "true" }
+                },
+                { "Driver": "cdi", "DeviceIDs": ["vendor.com/device=foo"] }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            DockerProcessor::extract_devices(&host_config).unwrap(),
+            vec!["/dev/fuse:/dev/fuse:rwm", "vendor.com/device=foo"]
+        );
+
+        let devices = DockerProcessor::extract_device_reservations(&host_config).unwrap();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].driver, None);
+        assert_eq!(devices[0].count, Some(DeviceCount::All("all".to_string())));
+        assert_eq!(devices[0].capabilities, vec!["gpu"]);
+        assert_eq!(devices[1].driver.as_deref(), Some("nvidia"));
+        assert_eq!(devices[1].count, None);
+        assert_eq!(devices[1].device_ids.as_deref().unwrap(), ["0", "3"]);
+        assert_eq!(devices[1].capabilities, vec!["gpu", "utility"]);
+        assert_eq!(devices[1].options.as_ref().unwrap()["visible"], "true");
+
+        let deploy = DockerProcessor::extract_deploy_config(&host_config).unwrap();
+        let reservations = deploy.resources.unwrap().reservations.unwrap();
+        assert!(reservations.memory.is_none());
+        assert_eq!(reservations.devices.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_pick_image_reference() {
+        let image: ImageInspect = serde_json::from_value(serde_json::json!({
+            "Id": "sha256:4f2c0d1e",
+            "RepoTags": ["registry.local/app:2.1", "registry.local/app:latest"],
+            "RepoDigests": ["registry.local/app@sha256:9b1e"]
+        }))
+        .unwrap();
+        let references = ImageInfo::from(image);
+
+        assert_eq!(
+            references.pick(ImagePreference::Tag).as_deref(),
+            Some("registry.local/app:2.1")
+        );
+        assert_eq!(
+            references.pick(ImagePreference::Digest).as_deref(),
+            Some("registry.local/app@sha256:9b1e")
+        );
+
+        // A moved tag leaves only the digest behind
+        let untagged: ImageInspect = serde_json::from_value(serde_json::json!({
+            "RepoTags": ["<none>:<none>"],
+            "RepoDigests": ["registry.local/app@sha256:9b1e"]
+        }))
+        .unwrap();
+        assert_eq!(
+            ImageInfo::from(untagged).pick(ImagePreference::Tag).as_deref(),
+            Some("registry.local/app@sha256:9b1e")
+        );
     }
 }
@@ -0,0 +1,845 @@
+/*!
+Copyright (c) 2025 Olympus Chain SAS
+
+This software is licensed under the Olympus Chain Internal Source License (OCISL).
+You may read and modify this code for personal or internal non-commercial use only.
+Commercial use, redistribution, or reuse in other software is prohibited without prior written permission.
+
+Contact: contact@olympus-chain.fr
+*/
+
+use crate::{
+    parse_size_bytes,
+    run_script::{gpus, is_gpu},
+    security::is_sensitive_env_var,
+    ComposeFile, DeviceCount, DeviceReservation, HealthCheck, Result, Service, ServiceVolume,
+};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Storage requested for every PersistentVolumeClaim; the running containers
+/// do not tell how much a volume needs.
+const DEFAULT_VOLUME_SIZE: &str = "1Gi";
+
+#[derive(Debug, Default)]
+pub struct KubernetesExport {
+    pub documents: Vec<Value>,
+    /// Settings Kubernetes has no equivalent for, left out of the manifests.
+    pub warnings: Vec<String>,
+}
+
+impl KubernetesExport {
+    /// All manifests as one multi-document YAML stream.
+    pub fn to_yaml(&self) -> Result<String> {
+        let documents = self
+            .documents
+            .iter()
+            .map(serde_yaml::to_string)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(documents.join("---\n"))
+    }
+}
+
+/// Converts a compose model into Deployment, Service, PersistentVolumeClaim,
+/// ConfigMap and Secret manifests.
+pub fn export_kubernetes(compose: &ComposeFile) -> KubernetesExport {
+    let mut export = KubernetesExport::default();
+    let mut claims = BTreeSet::new();
+    let mut manifests = Vec::new();
+
+    let mut names: Vec<&String> = compose.services.keys().collect();
+    names.sort();
+    for name in names {
+        let service = &compose.services[name];
+        let mut converter = ServiceConverter {
+            name: resource_name(name),
+            service,
+            warnings: &mut export.warnings,
+            claims: &mut claims,
+        };
+        manifests.extend(converter.manifests());
+    }
+
+    export.documents = claims
+        .iter()
+        .map(|claim| {
+            json!({
+                "apiVersion": "v1",
+                "kind": "PersistentVolumeClaim",
+                "metadata": { "name": claim },
+                "spec": {
+                    "accessModes": ["ReadWriteOnce"],
+                    "resources": { "requests": { "storage": DEFAULT_VOLUME_SIZE } },
+                },
+            })
+        })
+        .chain(manifests)
+        .collect();
+    export
+}
+
+struct ServiceConverter<'a> {
+    name: String,
+    service: &'a Service,
+    warnings: &'a mut Vec<String>,
+    claims: &'a mut BTreeSet<String>,
+}
+
+impl ServiceConverter<'_> {
+    fn warn(&mut self, message: impl std::fmt::Display) {
+        self.warnings.push(format!("{}: {}", self.name, message));
+    }
+
+    fn manifests(&mut self) -> Vec<Value> {
+        let service = self.service;
+        let name = self.name.clone();
+        let mut documents = Vec::new();
+        let mut container = Map::new();
+        container.insert("name".into(), json!(name));
+        container.insert("image".into(), json!(service.image));
+
+        // Credentials go to a Secret, everything else to a ConfigMap
+        let (secret, config): (BTreeMap<_, _>, BTreeMap<_, _>) = service
+            .environment
+            .iter()
+            .flatten()
+            .partition(|(key, _)| is_sensitive_env_var(key));
+        let mut env_from = Vec::new();
+        if !config.is_empty() {
+            documents.push(json!({
+                "apiVersion": "v1",
+                "kind": "ConfigMap",
+                "metadata": { "name": format!("{}-env", name) },
+                "data": config,
+            }));
+            env_from.push(json!({ "configMapRef": { "name": format!("{}-env", name) } }));
+        }
+        if !secret.is_empty() {
+            documents.push(json!({
+                "apiVersion": "v1",
+                "kind": "Secret",
+                "metadata": { "name": format!("{}-secret", name) },
+                "type": "Opaque",
+                "stringData": secret,
+            }));
+            env_from.push(json!({ "secretRef": { "name": format!("{}-secret", name) } }));
+        }
+        if !env_from.is_empty() {
+            container.insert("envFrom".into(), json!(env_from));
+        }
+
+        if let Some(entrypoint) = &service.entrypoint {
+            container.insert("command".into(), json!(entrypoint));
+        }
+        if let Some(command) = &service.command {
+            container.insert("args".into(), json!(command));
+        }
+        if let Some(dir) = &service.working_dir {
+            container.insert("workingDir".into(), json!(dir));
+        }
+        if service.tty == Some(true) {
+            container.insert("tty".into(), json!(true));
+        }
+        if service.stdin_open == Some(true) {
+            container.insert("stdin".into(), json!(true));
+        }
+
+        let ports = self.ports();
+        if !ports.is_empty() {
+            let container_ports: Vec<Value> = ports
+                .iter()
+                .map(|p| json!({ "containerPort": p.target, "protocol": p.protocol }))
+                .collect();
+            container.insert("ports".into(), json!(container_ports));
+        }
+
+        if let Some(healthcheck) = &service.healthcheck {
+            if let Some(probe) = self.probe(healthcheck) {
+                container.insert("livenessProbe".into(), probe.clone());
+                container.insert("readinessProbe".into(), probe);
+            }
+        }
+
+        if let Some(resources) = self.resources() {
+            container.insert("resources".into(), resources);
+        }
+        if let Some(security_context) = self.security_context() {
+            container.insert("securityContext".into(), security_context);
+        }
+
+        let (mounts, volumes) = self.volumes();
+        if !mounts.is_empty() {
+            container.insert("volumeMounts".into(), json!(mounts));
+        }
+
+        let mut pod_spec = Map::new();
+        pod_spec.insert("containers".into(), json!([container]));
+        if !volumes.is_empty() {
+            pod_spec.insert("volumes".into(), json!(volumes));
+        }
+        self.pod_settings(&mut pod_spec);
+
+        let mut metadata = json!({ "name": name, "labels": { "app": name } });
+        if let Some(labels) = service.labels.as_ref().filter(|l| !l.is_empty()) {
+            let labels: BTreeMap<_, _> = labels.iter().collect();
+            metadata["annotations"] = json!(labels);
+        }
+
+        let mut template_metadata = json!({ "labels": { "app": name } });
+        if let Some(annotations) = &service.annotations {
+            let annotations: BTreeMap<_, _> = annotations.iter().collect();
+            template_metadata["annotations"] = json!(annotations);
+        }
+
+        let replicas = service
+            .deploy
+            .as_ref()
+            .and_then(|d| d.replicas)
+            .unwrap_or(1);
+        documents.push(json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": metadata,
+            "spec": {
+                "replicas": replicas,
+                "selector": { "matchLabels": { "app": name } },
+                "template": {
+                    "metadata": template_metadata,
+                    "spec": pod_spec,
+                },
+            },
+        }));
+
+        if !ports.is_empty() {
+            let service_ports: Vec<Value> = ports
+                .iter()
+                .map(|p| {
+                    json!({
+                        "name": format!("{}-{}", p.protocol.to_lowercase(), p.published),
+                        "port": p.published,
+                        "targetPort": p.target,
+                        "protocol": p.protocol,
+                    })
+                })
+                .collect();
+            documents.push(json!({
+                "apiVersion": "v1",
+                "kind": "Service",
+                "metadata": { "name": name, "labels": { "app": name } },
+                "spec": {
+                    "selector": { "app": name },
+                    "ports": service_ports,
+                },
+            }));
+        }
+
+        self.unsupported();
+        documents
+    }
+
+    fn ports(&mut self) -> Vec<Port> {
+        let mut ports = Vec::new();
+        for spec in self.service.ports.iter().flatten() {
+            match Port::parse(spec) {
+                Some(port) => ports.push(port),
+                None => self.warn(format!("port {}", spec)),
+            }
+        }
+        // Exposed ports are only reachable by other services, on their own number
+        for spec in self.service.expose.iter().flatten() {
+            match Port::parse(spec) {
+                Some(port) if ports.iter().any(|p| p.target == port.target) => {}
+                Some(port) => ports.push(port),
+                None => self.warn(format!("expose {}", spec)),
+            }
+        }
+        ports
+    }
+
+    fn probe(&mut self, healthcheck: &HealthCheck) -> Option<Value> {
+        let command = match healthcheck.test.split_first() {
+            Some((kind, args)) if kind == "CMD" => json!(args),
+            Some((kind, args)) if kind == "CMD-SHELL" => json!(["sh", "-c", args.join(" ")]),
+            Some((kind, _)) if kind == "NONE" => return None,
+            _ => {
+                self.warn("healthcheck without a CMD or CMD-SHELL test");
+                return None;
+            }
+        };
+
+        let mut probe = json!({ "exec": { "command": command } });
+        let seconds = |duration: &Option<String>| duration.as_deref().and_then(duration_seconds);
+        if let Some(period) = seconds(&healthcheck.interval) {
+            probe["periodSeconds"] = json!(period.max(1));
+        }
+        if let Some(timeout) = seconds(&healthcheck.timeout) {
+            probe["timeoutSeconds"] = json!(timeout.max(1));
+        }
+        if let Some(delay) = seconds(&healthcheck.start_period) {
+            probe["initialDelaySeconds"] = json!(delay);
+        }
+        if let Some(retries) = healthcheck.retries.filter(|r| *r > 0) {
+            probe["failureThreshold"] = json!(retries);
+        }
+        Some(probe)
+    }
+
+    fn resources(&self) -> Option<Value> {
+        let resources = self.service.deploy.as_ref()?.resources.as_ref()?;
+        let quantities = |cpus: &Option<String>, memory: &Option<String>| {
+            let mut values = Map::new();
+            if let Some(cpus) = cpus {
+                values.insert("cpu".into(), json!(cpus));
+            }
+            if let Some(memory) = memory {
+                values.insert("memory".into(), json!(memory_quantity(memory)));
+            }
+            (!values.is_empty()).then_some(values)
+        };
+
+        let mut values = Map::new();
+        let mut limits = resources
+            .limits
+            .as_ref()
+            .and_then(|l| quantities(&l.cpus, &l.memory))
+            .unwrap_or_default();
+        // Extended resources are only set as limits; the device plugin picks the GPUs
+        let gpu_count: u64 = gpus(self.service).filter_map(gpu_count).sum();
+        if gpu_count > 0 {
+            limits.insert("nvidia.com/gpu".into(), json!(gpu_count));
+        }
+        if !limits.is_empty() {
+            values.insert("limits".into(), json!(limits));
+        }
+        if let Some(requests) = resources
+            .reservations
+            .as_ref()
+            .and_then(|r| quantities(&r.cpus, &r.memory))
+        {
+            values.insert("requests".into(), json!(requests));
+        }
+        (!values.is_empty()).then(|| json!(values))
+    }
+
+    fn security_context(&self) -> Option<Value> {
+        let service = self.service;
+        let mut context = Map::new();
+
+        if service.privileged == Some(true) {
+            context.insert("privileged".into(), json!(true));
+        }
+        if service.read_only == Some(true) {
+            context.insert("readOnlyRootFilesystem".into(), json!(true));
+        }
+
+        let capabilities = |caps: &Option<Vec<String>>| -> Vec<String> {
+            caps.iter()
+                .flatten()
+                .map(|cap| cap.trim_start_matches("CAP_").to_string())
+                .collect()
+        };
+        let mut caps = Map::new();
+        let add = capabilities(&service.cap_add);
+        if !add.is_empty() {
+            caps.insert("add".into(), json!(add));
+        }
+        let drop = capabilities(&service.cap_drop);
+        if !drop.is_empty() {
+            caps.insert("drop".into(), json!(drop));
+        }
+        if !caps.is_empty() {
+            context.insert("capabilities".into(), Value::Object(caps));
+        }
+
+        // Only numeric IDs can be expressed; names depend on the image
+        if let Some(user) = &service.user {
+            let (uid, gid) = user.split_once(':').unwrap_or((user, ""));
+            if let Ok(uid) = uid.parse::<u64>() {
+                context.insert("runAsUser".into(), json!(uid));
+            }
+            if let Ok(gid) = gid.parse::<u64>() {
+                context.insert("runAsGroup".into(), json!(gid));
+            }
+        }
+
+        (!context.is_empty()).then_some(Value::Object(context))
+    }
+
+    fn volumes(&mut self) -> (Vec<Value>, Vec<Value>) {
+        let mut mounts = Vec::new();
+        let mut volumes = Vec::new();
+
+        for (index, volume) in self.service.volumes.iter().flatten().enumerate() {
+            let (kind, source, target, read_only) = match volume {
+                ServiceVolume::Short(spec) => {
+                    let parts: Vec<&str> = spec.split(':').collect();
+                    if parts.len() < 2 {
+                        self.warn(format!("anonymous volume {}", spec));
+                        continue;
+                    }
+                    let kind = if parts[0].starts_with(['/', '.', '~']) {
+                        "bind"
+                    } else {
+                        "volume"
+                    };
+                    let read_only = parts
+                        .get(2)
+                        .is_some_and(|o| o.split(',').any(|o| o == "ro"));
+                    (kind, parts[0].to_string(), parts[1].to_string(), read_only)
+                }
+                ServiceVolume::Long(mount) => (
+                    mount.mount_type.as_str(),
+                    mount.source.clone().unwrap_or_default(),
+                    mount.target.clone(),
+                    mount.read_only == Some(true),
+                ),
+            };
+
+            let volume_name = format!("{}-{}", self.name, index);
+            let definition = match kind {
+                "volume" if !source.is_empty() => {
+                    let claim = resource_name(&source);
+                    self.claims.insert(claim.clone());
+                    json!({ "name": volume_name, "persistentVolumeClaim": { "claimName": claim } })
+                }
+                "bind" if source.starts_with('/') => {
+                    self.warn(format!("bind mount {} became a hostPath volume", source));
+                    json!({ "name": volume_name, "hostPath": { "path": source } })
+                }
+                "tmpfs" => json!({ "name": volume_name, "emptyDir": { "medium": "Memory" } }),
+                _ => {
+                    self.warn(format!("{} mount {}:{}", kind, source, target));
+                    continue;
+                }
+            };
+
+            let mut mount = json!({ "name": volume_name, "mountPath": target });
+            if read_only {
+                mount["readOnly"] = json!(true);
+            }
+            mounts.push(mount);
+            volumes.push(definition);
+        }
+
+        for (index, tmpfs) in self.service.tmpfs.iter().flatten().enumerate() {
+            let volume_name = format!("{}-tmpfs-{}", self.name, index);
+            let target = tmpfs.split(':').next().unwrap_or(tmpfs);
+            mounts.push(json!({ "name": volume_name, "mountPath": target }));
+            volumes.push(json!({ "name": volume_name, "emptyDir": { "medium": "Memory" } }));
+        }
+
+        (mounts, volumes)
+    }
+
+    fn pod_settings(&mut self, pod_spec: &mut Map<String, Value>) {
+        let service = self.service;
+
+        match service.network_mode.as_deref() {
+            Some("host") => {
+                pod_spec.insert("hostNetwork".into(), json!(true));
+            }
+            Some(mode) if mode.starts_with("service:") || mode.starts_with("container:") => {
+                self.warn(format!(
+                    "network_mode {} (run it as a sidecar instead)",
+                    mode
+                ));
+            }
+            _ => {}
+        }
+
+        match service.pid.as_deref() {
+            Some("host") => {
+                pod_spec.insert("hostPID".into(), json!(true));
+            }
+            Some(mode) => self.warn(format!("pid {}", mode)),
+            None => {}
+        }
+        match service.ipc.as_deref() {
+            Some("host") => {
+                pod_spec.insert("hostIPC".into(), json!(true));
+            }
+            // Containers of a pod already share their IPC namespace
+            Some("private" | "shareable") | None => {}
+            Some(mode) => self.warn(format!("ipc {}", mode)),
+        }
+        for (key, value) in [
+            ("uts", &service.uts),
+            ("userns_mode", &service.userns_mode),
+            ("cgroup", &service.cgroup),
+            ("cgroup_parent", &service.cgroup_parent),
+            ("isolation", &service.isolation),
+        ] {
+            if let Some(value) = value {
+                self.warn(format!("{} {}", key, value));
+            }
+        }
+        if let Some(runtime) = &service.runtime {
+            // Assumes a RuntimeClass named after the runtime
+            pod_spec.insert("runtimeClassName".into(), json!(resource_name(runtime)));
+        }
+
+        if let Some(hostname) = &service.hostname {
+            pod_spec.insert("hostname".into(), json!(resource_name(hostname)));
+        }
+        if let Some(platform) = &service.platform {
+            // Nodes are only labelled with their OS and architecture, not the variant
+            let mut selector = Map::new();
+            let keys = ["kubernetes.io/os", "kubernetes.io/arch"];
+            for (key, value) in keys.into_iter().zip(platform.split('/')) {
+                selector.insert(key.into(), json!(value));
+            }
+            pod_spec.insert("nodeSelector".into(), json!(selector));
+        }
+
+        if let Some(seconds) = service
+            .stop_grace_period
+            .as_deref()
+            .and_then(duration_seconds)
+        {
+            pod_spec.insert("terminationGracePeriodSeconds".into(), json!(seconds));
+        }
+        let mut security_context = Map::new();
+        let mut groups = Vec::new();
+        for group in service.group_add.iter().flatten() {
+            // Group names cannot be resolved outside the image
+            match group.parse::<u64>() {
+                Ok(gid) => groups.push(gid),
+                Err(_) => self.warn(format!("group_add {}", group)),
+            }
+        }
+        if !groups.is_empty() {
+            security_context.insert("supplementalGroups".into(), json!(groups));
+        }
+        if let Some(sysctls) = service.sysctls.as_ref().filter(|s| !s.is_empty()) {
+            let sysctls: BTreeMap<_, _> = sysctls.iter().collect();
+            let sysctls: Vec<Value> = sysctls
+                .into_iter()
+                .map(|(name, value)| json!({ "name": name, "value": value }))
+                .collect();
+            security_context.insert("sysctls".into(), json!(sysctls));
+        }
+        if !security_context.is_empty() {
+            pod_spec.insert("securityContext".into(), Value::Object(security_context));
+        }
+        for (key, value) in [
+            ("stop_signal", &service.stop_signal),
+            ("domainname", &service.domainname),
+            ("mac_address", &service.mac_address),
+        ] {
+            if let Some(value) = value {
+                self.warn(format!("{} {}", key, value));
+            }
+        }
+
+        let mut aliases: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for entry in service.extra_hosts.iter().flatten() {
+            // Docker writes host:ip, compose also accepts host=ip
+            match entry.split_once([':', '=']) {
+                Some((host, ip)) => aliases.entry(ip.to_string()).or_default().push(host.into()),
+                None => self.warn(format!("extra host {}", entry)),
+            }
+        }
+        if !aliases.is_empty() {
+            let aliases: Vec<Value> = aliases
+                .into_iter()
+                .map(|(ip, hostnames)| json!({ "ip": ip, "hostnames": hostnames }))
+                .collect();
+            pod_spec.insert("hostAliases".into(), json!(aliases));
+        }
+
+        let mut dns_config = Map::new();
+        if let Some(servers) = service.dns.as_ref().filter(|d| !d.is_empty()) {
+            dns_config.insert("nameservers".into(), json!(servers));
+        }
+        if let Some(searches) = service.dns_search.as_ref().filter(|d| !d.is_empty()) {
+            dns_config.insert("searches".into(), json!(searches));
+        }
+        if !dns_config.is_empty() {
+            pod_spec.insert("dnsConfig".into(), Value::Object(dns_config));
+        }
+    }
+
+    fn unsupported(&mut self) {
+        let service = self.service;
+        if matches!(service.restart.as_deref(), Some("no") | Some("on-failure"))
+            || service
+                .restart
+                .as_deref()
+                .is_some_and(|r| r.starts_with("on-failure:"))
+        {
+            self.warn("restart policy (Deployments always restart their pods)");
+        }
+        if service.depends_on.is_some() {
+            self.warn("depends_on (Kubernetes starts pods independently)");
+        }
+        if service.devices.is_some() {
+            self.warn("devices");
+        }
+        let devices = service
+            .deploy
+            .as_ref()
+            .and_then(|d| d.resources.as_ref())
+            .and_then(|r| r.reservations.as_ref())
+            .and_then(|r| r.devices.as_ref());
+        if devices
+            .into_iter()
+            .flatten()
+            .any(|device| !is_gpu(device) || gpu_count(device).is_none())
+        {
+            self.warn("device reservations other than a number of NVIDIA GPUs");
+        }
+        if service.ulimits.is_some() {
+            self.warn("ulimits");
+        }
+        if service.logging.is_some() {
+            self.warn("logging");
+        }
+        if service.security_opt.is_some() {
+            self.warn("security_opt");
+        }
+        // Engine-level controls that Kubernetes does not expose per container
+        for (key, set) in [
+            ("cpu_shares", service.cpu_shares.is_some()),
+            ("cpu_period", service.cpu_period.is_some()),
+            ("cpu_quota", service.cpu_quota.is_some()),
+            ("cpuset", service.cpuset.is_some()),
+            ("mem_swappiness", service.mem_swappiness.is_some()),
+            ("memswap_limit", service.memswap_limit.is_some()),
+            ("pids_limit", service.pids_limit.is_some()),
+            ("oom_kill_disable", service.oom_kill_disable.is_some()),
+            ("oom_score_adj", service.oom_score_adj.is_some()),
+            ("shm_size", service.shm_size.is_some()),
+            ("blkio_config", service.blkio_config.is_some()),
+            ("storage_opt", service.storage_opt.is_some()),
+            ("device_cgroup_rules", service.device_cgroup_rules.is_some()),
+        ] {
+            if set {
+                self.warn(key);
+            }
+        }
+        if service
+            .deploy
+            .as_ref()
+            .is_some_and(|d| d.placement.is_some())
+        {
+            self.warn("deploy.placement");
+        }
+    }
+}
+
+struct Port {
+    published: u16,
+    target: u16,
+    protocol: String,
+}
+
+impl Port {
+    /// Parses `[ip:][published:]target[/protocol]`; ranges are not supported.
+    fn parse(spec: &str) -> Option<Self> {
+        let (ports, protocol) = spec.rsplit_once('/').unwrap_or((spec, "tcp"));
+        let mut parts = ports.rsplit(':');
+        let target: u16 = parts.next()?.parse().ok()?;
+        let published = match parts.next() {
+            Some(published) if !published.is_empty() => published.parse().ok()?,
+            _ => target,
+        };
+        Some(Self {
+            published,
+            target,
+            protocol: protocol.to_uppercase(),
+        })
+    }
+}
+
+/// The number of GPUs a reservation asks for, when it names neither all nor specific ones.
+fn gpu_count(device: &DeviceReservation) -> Option<u64> {
+    match (&device.count, &device.device_ids) {
+        (Some(DeviceCount::Count(count)), None) => Some(*count),
+        _ => None,
+    }
+}
+
+/// Turns a compose name into a DNS-1123 label.
+fn resource_name(name: &str) -> String {
+    let name: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let name = name.trim_matches('-');
+    name[..name.len().min(63)].trim_end_matches('-').to_string()
+}
+
+/// Compose sizes are binary (`512m` is 512 MiB), while a lowercase `m` means
+/// milli in Kubernetes.
+fn memory_quantity(memory: &str) -> String {
+    const MIB: i64 = 1024 * 1024;
+    match parse_size_bytes(memory) {
+        Some(bytes) if bytes % (1024 * MIB) == 0 => format!("{}Gi", bytes / (1024 * MIB)),
+        Some(bytes) if bytes % MIB == 0 => format!("{}Mi", bytes / MIB),
+        Some(bytes) => bytes.to_string(),
+        None => memory.to_string(),
+    }
+}
+
+/// Parses durations such as `30s`, `1m30s` or `2h`, rounding down to seconds.
+pub(crate) fn duration_seconds(duration: &str) -> Option<i64> {
+    let mut total_ms = 0i64;
+    let mut number = String::new();
+    let mut chars = duration.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let value: i64 = number.parse().ok()?;
+        number.clear();
+        total_ms += match c {
+            'h' => value * 3_600_000,
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                value
+            }
+            'm' => value * 60_000,
+            's' => value * 1000,
+            _ => return None,
+        };
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    Some(total_ms / 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(yaml: &str) -> KubernetesExport {
+        export_kubernetes(&serde_yaml::from_str(yaml).unwrap())
+    }
+
+    fn document<'a>(export: &'a KubernetesExport, kind: &str, name: &str) -> &'a Value {
+        export
+            .documents
+            .iter()
+            .find(|d| d["kind"] == kind && d["metadata"]["name"] == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_deployment_and_service() {
+        let export = export(
+            r#"
+version: "3.9"
+services:
+  web_app:
+    image: acme/web:2.0
+    platform: linux/arm64
+    ports: ["8080:80/tcp"]
+    expose: ["80", "9113"]
+    environment:
+      LOG_LEVEL: info
+      DB_PASSWORD: hunter2
+    volumes:
+      - uploads:/srv/uploads
+    healthcheck:
+      test: [CMD, curl, -f, http://localhost/]
+      interval: 1m30s
+      retries: 3
+    cap_add: [NET_ADMIN]
+    user: "1000:1000"
+    read_only: true
+    stop_grace_period: 45s
+    deploy:
+      replicas: 2
+      resources:
+        limits:
+          cpus: "0.5"
+          memory: 512m
+        reservations:
+          memory: 256m
+          devices:
+            - capabilities: [gpu]
+              count: 1
+"#,
+        );
+
+        let deployment = document(&export, "Deployment", "web-app");
+        assert_eq!(deployment["spec"]["replicas"], 2);
+        let container = &deployment["spec"]["template"]["spec"]["containers"][0];
+        assert_eq!(container["ports"][0]["containerPort"], 80);
+        assert_eq!(container["ports"][1]["containerPort"], 9113);
+        assert_eq!(
+            deployment["spec"]["template"]["spec"]["nodeSelector"]["kubernetes.io/arch"],
+            "arm64"
+        );
+        assert_eq!(container["livenessProbe"]["periodSeconds"], 90);
+        assert_eq!(container["readinessProbe"]["failureThreshold"], 3);
+        assert_eq!(container["resources"]["limits"]["memory"], "512Mi");
+        assert_eq!(container["resources"]["requests"]["memory"], "256Mi");
+        assert_eq!(container["resources"]["limits"]["nvidia.com/gpu"], 1);
+        assert_eq!(
+            container["securityContext"]["capabilities"]["add"][0],
+            "NET_ADMIN"
+        );
+        assert_eq!(container["securityContext"]["runAsGroup"], 1000);
+        assert_eq!(container["securityContext"]["readOnlyRootFilesystem"], true);
+        assert_eq!(
+            deployment["spec"]["template"]["spec"]["terminationGracePeriodSeconds"],
+            45
+        );
+        assert_eq!(
+            container["envFrom"][1]["secretRef"]["name"],
+            "web-app-secret"
+        );
+
+        let service = document(&export, "Service", "web-app");
+        assert_eq!(service["spec"]["ports"][0]["port"], 8080);
+        assert_eq!(service["spec"]["ports"][0]["targetPort"], 80);
+        assert_eq!(service["spec"]["ports"][1]["port"], 9113);
+        assert_eq!(
+            document(&export, "ConfigMap", "web-app-env")["data"]["LOG_LEVEL"],
+            "info"
+        );
+        assert_eq!(
+            document(&export, "Secret", "web-app-secret")["stringData"]["DB_PASSWORD"],
+            "hunter2"
+        );
+        assert_eq!(export.documents[0]["kind"], "PersistentVolumeClaim");
+        assert!(export.warnings.is_empty());
+
+        let yaml = export.to_yaml().unwrap();
+        assert_eq!(yaml.matches("\n---\n").count(), export.documents.len() - 1);
+    }
+
+    #[test]
+    fn test_pod_security_context() {
+        let export = export(
+            r#"
+version: "3.9"
+services:
+  db:
+    image: postgres:16
+    group_add: ["70", "1001"]
+    sysctls:
+      net.core.somaxconn: "1024"
+"#,
+        );
+
+        let pod = &document(&export, "Deployment", "db")["spec"]["template"]["spec"];
+        let security_context = &pod["securityContext"];
+        assert_eq!(security_context["supplementalGroups"], json!([70, 1001]));
+        assert_eq!(security_context["sysctls"][0]["name"], "net.core.somaxconn");
+        assert_eq!(security_context["sysctls"][0]["value"], "1024");
+    }
+
+    #[test]
+    fn test_duration_seconds() {
+        assert_eq!(duration_seconds("30s"), Some(30));
+        assert_eq!(duration_seconds("1m30s"), Some(90));
+        assert_eq!(duration_seconds("2h"), Some(7200));
+        assert_eq!(duration_seconds("1500ms"), Some(1));
+        assert_eq!(duration_seconds("soon"), None);
+    }
+}
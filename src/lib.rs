@@ -10,15 +10,23 @@ Contact: contact@olympus-chain.fr
 
 pub mod cli;
 pub mod constants;
+pub mod dependencies;
 pub mod docker;
+pub mod kubernetes;
 pub mod podman;
+pub mod projects;
+pub mod pruning;
+pub mod quadlet;
+pub mod replicas;
+pub mod run_script;
 pub mod security;
+pub mod swarm;
 pub mod validation;
 
 pub use docker::ProcessingOptions;
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -58,17 +66,94 @@ pub enum ServiceNetworks {
     Advanced(HashMap<String, NetworkConfig>),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct NetworkConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aliases: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ipv4_address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ipv6_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_local_ips: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver_opts: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gw_priority: Option<i64>,
+}
+
+impl NetworkConfig {
+    /// True when the endpoint carries nothing worth writing out.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_none()
+            && self.ipv4_address.is_none()
+            && self.ipv6_address.is_none()
+            && self.link_local_ips.is_none()
+            && self.mac_address.is_none()
+            && self.driver_opts.is_none()
+            && self.gw_priority.is_none()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ServiceVolume {
+    Short(String),
+    Long(VolumeMount),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VolumeMount {
+    #[serde(rename = "type")]
+    pub mount_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind: Option<BindOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<VolumeOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tmpfs: Option<TmpfsOptions>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+pub struct BindOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub propagation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_host_path: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selinux: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VolumeOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nocopy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TmpfsOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+    /// File mode written with its octal digits, e.g. 1777
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Service {
     pub image: String,
+    /// `os/architecture[/variant]` of the image the container runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub container_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -77,8 +162,13 @@ pub struct Service {
     pub environment: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ports: Option<Vec<String>>,
+    /// Ports the container exposes without publishing them.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub volumes: Option<Vec<String>>,
+    pub expose: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volumes: Option<Vec<ServiceVolume>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tmpfs: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub networks: Option<ServiceNetworks>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -98,6 +188,46 @@ pub struct Service {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub security_opt: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uts: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub userns_mode: Option<String>,
+    /// Cgroup namespace mode, `host` or `private` (the engine's `CgroupnsMode`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cgroup: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cgroup_parent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isolation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_shares: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_period: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_quota: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpuset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mem_swappiness: Option<i64>,
+    /// Memory plus swap, `-1` for unlimited swap.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memswap_limit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pids_limit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oom_kill_disable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oom_score_adj: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shm_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blkio_config: Option<BlkioConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub deploy: Option<Deploy>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub healthcheck: Option<HealthCheck>,
@@ -128,24 +258,169 @@ pub struct Service {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stdin_open: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub depends_on: Option<Vec<String>>,
+    pub read_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_signal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_grace_period: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_add: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domainname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_opt: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_cgroup_rules: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<Vec<FileReference>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configs: Option<Vec<FileReference>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depends_on: Option<DependsOn>,
+    #[serde(rename = "x-podman", skip_serializing_if = "Option::is_none")]
+    pub x_podman: Option<PodmanExtension>,
+    /// Labels left by compose on the container, used to restore its project.
+    #[serde(skip)]
+    pub compose: Option<projects::ComposeLabels>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A secret or config mounted into the service, in the long syntax.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileReference {
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+}
+
+/// Podman specific settings that compose has no key for.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct PodmanExtension {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pod: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pod_labels: Option<HashMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum DependsOn {
+    Simple(Vec<String>),
+    Extended(HashMap<String, DependencyConfig>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DependencyConfig {
+    pub condition: DependencyCondition,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyCondition {
+    #[default]
+    ServiceStarted,
+    ServiceHealthy,
+    ServiceCompletedSuccessfully,
+}
+
+impl DependsOn {
+    /// Names of the services depended on, sorted for the long form.
+    pub fn services(&self) -> Vec<&str> {
+        match self {
+            DependsOn::Simple(services) => services.iter().map(|s| s.as_str()).collect(),
+            DependsOn::Extended(services) => {
+                let mut names: Vec<&str> = services.keys().map(|s| s.as_str()).collect();
+                names.sort_unstable();
+                names
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            DependsOn::Simple(services) => services.len(),
+            DependsOn::Extended(services) => services.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, service: &str) -> bool {
+        match self {
+            DependsOn::Simple(services) => services.iter().any(|s| s == service),
+            DependsOn::Extended(services) => services.contains_key(service),
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        match self {
+            DependsOn::Simple(services) => services.retain(|s| keep(s)),
+            DependsOn::Extended(services) => services.retain(|s, _| keep(s)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Deploy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replicas: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resources: Option<Resources>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_policy: Option<RestartPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub placement: Option<Placement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_config: Option<UpdateConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollback_config: Option<UpdateConfig>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Resources {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limits: Option<ResourceLimits>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reservations: Option<ResourceReservations>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl Resources {
+    /// Combines limits and reservations, leaving out the empty ones.
+    pub fn new(limits: ResourceLimits, reservations: ResourceReservations) -> Option<Self> {
+        let limits = (limits.cpus.is_some() || limits.memory.is_some()).then_some(limits);
+        let reservations = (reservations.cpus.is_some()
+            || reservations.memory.is_some()
+            || reservations.devices.is_some())
+        .then_some(reservations);
+        (limits.is_some() || reservations.is_some()).then_some(Self {
+            limits,
+            reservations,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct ResourceLimits {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cpus: Option<String>,
@@ -153,13 +428,123 @@ pub struct ResourceLimits {
     pub memory: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ResourceReservations {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub devices: Option<Vec<DeviceReservation>>,
+}
+
+/// A device the container reserves, such as the GPUs of `--gpus`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DeviceReservation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<DeviceCount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_ids: Option<Vec<String>>,
+    pub capabilities: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<HashMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum DeviceCount {
+    Count(u64),
+    /// `all`
+    All(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BlkioConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight_device: Option<Vec<BlkioWeightDevice>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_read_bps: Option<Vec<BlkioThrottle>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_write_bps: Option<Vec<BlkioThrottle>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_read_iops: Option<Vec<BlkioThrottle>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_write_iops: Option<Vec<BlkioThrottle>>,
+}
+
+impl BlkioConfig {
+    pub fn is_empty(&self) -> bool {
+        self.weight.is_none()
+            && self.weight_device.is_none()
+            && self.device_read_bps.is_none()
+            && self.device_write_bps.is_none()
+            && self.device_read_iops.is_none()
+            && self.device_write_iops.is_none()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BlkioWeightDevice {
+    pub path: String,
+    pub weight: u16,
+}
+
+/// A per-device limit, in bytes or operations per second.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BlkioThrottle {
+    pub path: String,
+    pub rate: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Placement {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub constraints: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferences: Option<Vec<PlacementPreference>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_replicas_per_node: Option<u64>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlacementPreference {
+    pub spread: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct RestartPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>,
+}
+
+/// `update_config` and `rollback_config` share one shape.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct UpdateConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallelism: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_failure_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct HealthCheck {
     pub test: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -179,14 +564,130 @@ pub struct Logging {
     pub options: Option<HashMap<String, String>>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct VolumeDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver_opts: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external: Option<bool>,
+}
+
+impl VolumeDefinition {
+    pub fn external(name: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            external: Some(true),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct NetworkDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver_opts: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipam: Option<IpamDefinition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enable_ipv6: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external: Option<bool>,
+}
+
+impl NetworkDefinition {
+    pub fn external(name: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            external: Some(true),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct IpamDefinition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<Vec<IpamPool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<HashMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct IpamPool {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gateway: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_range: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aux_addresses: Option<HashMap<String, String>>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ComposeFile {
-    pub version: String,
+    /// Obsolete in the Compose Specification, which leaves it out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Project name; only the Compose Specification knows this key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub services: HashMap<String, Service>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub networks: Option<HashMap<String, serde_yaml::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub volumes: Option<HashMap<String, serde_yaml::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secrets: Option<HashMap<String, serde_yaml::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configs: Option<HashMap<String, serde_yaml::Value>>,
+}
+
+impl ComposeFile {
+    /// The top-level networks, skipping entries that do not parse.
+    pub fn network_definitions(&self) -> BTreeMap<String, NetworkDefinition> {
+        definitions(self.networks.as_ref())
+    }
+
+    /// The top-level volumes, skipping entries that do not parse.
+    pub fn volume_definitions(&self) -> BTreeMap<String, VolumeDefinition> {
+        definitions(self.volumes.as_ref())
+    }
+}
+
+fn definitions<T: serde::de::DeserializeOwned>(
+    values: Option<&HashMap<String, serde_yaml::Value>>,
+) -> BTreeMap<String, T> {
+    values
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| {
+            // `name:` with nothing after it is a definition with defaults
+            let value = match value {
+                serde_yaml::Value::Null => serde_yaml::Value::Mapping(Default::default()),
+                value => value.clone(),
+            };
+            serde_yaml::from_value(value).ok().map(|def| (name.clone(), def))
+        })
+        .collect()
 }
 
 pub fn normalize_duration_from_ns(nanoseconds: i64) -> String {
@@ -202,6 +703,26 @@ pub fn normalize_duration_from_ns(nanoseconds: i64) -> String {
     }
 }
 
+/// Formats a whole number of seconds exactly, e.g. `90` as `1m30s`.
+pub fn format_duration_secs(seconds: i64) -> String {
+    use crate::constants::{SECONDS_PER_HOUR, SECONDS_PER_MINUTE};
+
+    if seconds <= 0 {
+        return "0s".to_string();
+    }
+    let hours = seconds / SECONDS_PER_HOUR;
+    let minutes = seconds % SECONDS_PER_HOUR / SECONDS_PER_MINUTE;
+    let seconds = seconds % SECONDS_PER_MINUTE;
+
+    let mut formatted = String::new();
+    for (count, unit) in [(hours, "h"), (minutes, "m"), (seconds, "s")] {
+        if count > 0 {
+            formatted.push_str(&format!("{}{}", count, unit));
+        }
+    }
+    formatted
+}
+
 pub fn normalize_duration(duration: &str) -> String {
     if duration.ends_with("ns") {
         if let Ok(ns) = duration.trim_end_matches("ns").parse::<i64>() {
@@ -211,6 +732,75 @@ pub fn normalize_duration(duration: &str) -> String {
     duration.to_string()
 }
 
+pub fn parse_size_bytes(size: &str) -> Option<i64> {
+    let size = size.trim().to_lowercase();
+    let digits_end = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(digits_end);
+    let value: i64 = number.parse().ok()?;
+
+    let multiplier = match unit.trim_end_matches('b') {
+        "" => 1,
+        "k" => 1024,
+        "m" => 1024 * 1024,
+        "g" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    value.checked_mul(multiplier)
+}
+
+/// Formats a byte count with the largest unit that divides it exactly, the
+/// inverse of [`parse_size_bytes`].
+pub fn format_size_bytes(bytes: i64) -> String {
+    const UNITS: [(i64, &str); 3] = [(1024 * 1024 * 1024, "G"), (1024 * 1024, "M"), (1024, "K")];
+    UNITS
+        .iter()
+        .find(|(size, _)| bytes != 0 && bytes % size == 0)
+        .map(|(size, unit)| format!("{}{}", bytes / size, unit))
+        .unwrap_or_else(|| bytes.to_string())
+}
+
+/// The `memswap_limit` of a container, or `None` when the engine picked it.
+/// Both Docker and Podman default the swap limit to twice the memory limit.
+pub fn memswap_limit(memory: i64, memory_swap: i64) -> Option<String> {
+    match memory_swap {
+        0 => None,
+        swap if memory > 0 && swap == memory * 2 => None,
+        swap => Some(format_size_bytes(swap)),
+    }
+}
+
+/// Labels set by docker compose and podman-compose on the resources they create
+pub const COMPOSE_PROJECT_LABELS: [&str; 3] = [
+    "com.docker.compose.project",
+    "io.podman.compose.project",
+    swarm::STACK_NAMESPACE_LABEL,
+];
+
+pub fn compose_project(labels: &HashMap<String, String>) -> Option<String> {
+    COMPOSE_PROJECT_LABELS
+        .iter()
+        .find_map(|key| labels.get(*key))
+        .filter(|project| !project.is_empty())
+        .cloned()
+}
+
+/// A network or volume is external when no compose project created it, or
+/// when containers outside the owning project use it as well.
+pub fn is_external_resource(owner: Option<&str>, users: &HashSet<Option<String>>) -> bool {
+    match owner {
+        None => true,
+        Some(owner) => users.iter().any(|user| user.as_deref() != Some(owner)),
+    }
+}
+
+pub fn is_anonymous_volume(name: &str) -> bool {
+    use crate::constants::IMAGE_HASH_LENGTH;
+
+    name.len() == IMAGE_HASH_LENGTH && name.chars().all(|c| c.is_ascii_hexdigit())
+}
+
 pub fn sanitize_service_name(name: &str) -> String {
     name.trim_start_matches('/')
         .chars()
@@ -242,6 +832,135 @@ pub fn filter_system_labels(labels: HashMap<String, String>) -> Option<HashMap<S
     }
 }
 
+/// Drops the annotations the engines add to every container on their own.
+pub fn filter_system_annotations(
+    annotations: HashMap<String, String>,
+) -> Option<HashMap<String, String>> {
+    filter_system_labels(annotations).and_then(|annotations| {
+        let filtered: HashMap<String, String> = annotations
+            .into_iter()
+            .filter(|(key, _)| {
+                key != "io.container.manager" && !key.starts_with("io.kubernetes.cri-o")
+            })
+            .collect();
+        (!filtered.is_empty()).then_some(filtered)
+    })
+}
+
+/// The `expose` form of an exposed port key: `80/tcp` becomes `80`.
+pub fn expose_entry(port: &str) -> String {
+    port.strip_suffix("/tcp").unwrap_or(port).to_string()
+}
+
+/// `expose` entries for the exposed ports that have no published binding.
+pub fn unpublished_ports<'a>(
+    exposed: impl IntoIterator<Item = &'a str>,
+    published: &HashSet<&str>,
+) -> Option<Vec<String>> {
+    let mut ports: Vec<&str> = exposed
+        .into_iter()
+        .filter(|port| !published.contains(port))
+        .collect();
+    ports.sort_by_key(|port| {
+        let number = port.split('/').next().and_then(|n| n.parse::<u16>().ok());
+        (number, port.to_string())
+    });
+    ports.dedup();
+    let ports: Vec<String> = ports.into_iter().map(expose_entry).collect();
+    (!ports.is_empty()).then_some(ports)
+}
+
+/// The compose `platform` of an image, leaving out the variant every
+/// `arm64` image has.
+pub fn image_platform(os: &str, architecture: &str, variant: &str) -> Option<String> {
+    if os.is_empty() || architecture.is_empty() {
+        return None;
+    }
+    Some(match variant {
+        "" => format!("{}/{}", os, architecture),
+        "v8" if architecture == "arm64" => format!("{}/{}", os, architecture),
+        variant => format!("{}/{}/{}", os, architecture, variant),
+    })
+}
+
+/// True for a CDI device name such as `nvidia.com/gpu=all`, as opposed to a device path.
+pub fn is_cdi_device(device: &str) -> bool {
+    device.split_once('=').is_some_and(|(kind, name)| {
+        kind.contains('/') && !kind.starts_with('/') && !name.is_empty()
+    })
+}
+
+/// A `tmpfs` entry in the `target[:options]` form of `--tmpfs`.
+pub fn tmpfs_entry(target: &str, options: &str) -> String {
+    if options.is_empty() {
+        target.to_string()
+    } else {
+        format!("{}:{}", target, options)
+    }
+}
+
+/// True for a full image ID, with or without the `sha256:` prefix.
+pub fn is_image_id(reference: &str) -> bool {
+    let hex = reference.strip_prefix("sha256:").unwrap_or(reference);
+    hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// True when an image reference already names a content digest.
+pub fn is_pinned_image(image: &str) -> bool {
+    image.contains("@sha256:")
+}
+
+/// Pins `image` to the registry digest recorded for its repository, giving
+/// `repo:tag@sha256:...`. Returns `None` when none of the digests belongs to
+/// the image's repository, e.g. for an image that was built locally.
+pub fn pin_image_digest(image: &str, repo_digests: &[String]) -> Option<String> {
+    if is_pinned_image(image) {
+        return Some(image.to_string());
+    }
+
+    // Docker Hub images are reported with or without their implicit prefixes
+    let normalize = |repo: &str| -> String {
+        let repo = repo.strip_prefix("docker.io/").unwrap_or(repo);
+        repo.strip_prefix("library/").unwrap_or(repo).to_string()
+    };
+
+    // A tag follows the last colon, unless that colon belongs to a registry port
+    let repository = match image.rfind(':') {
+        Some(colon) if !image[colon..].contains('/') => &image[..colon],
+        _ => image,
+    };
+    let repository = normalize(repository);
+
+    repo_digests.iter().find_map(|entry| {
+        let (repo, digest) = entry.split_once('@')?;
+        (normalize(repo) == repository).then(|| format!("{}@{}", image, digest))
+    })
+}
+
+/// Drops the aliases the engine adds on its own (the short container ID, the
+/// container name and the service name), which compose recreates anyway.
+pub fn filter_endpoint_aliases(
+    aliases: Vec<String>,
+    container_id: &str,
+    names: &[&str],
+) -> Option<Vec<String>> {
+    let short_id = &container_id[..container_id.len().min(12)];
+    let mut filtered: Vec<String> = aliases
+        .into_iter()
+        .filter(|alias| {
+            !alias.is_empty() && alias != short_id && alias != container_id
+                && !names.contains(&alias.as_str())
+        })
+        .collect();
+    filtered.dedup();
+
+    if filtered.is_empty() {
+        None
+    } else {
+        Some(filtered)
+    }
+}
+
 pub trait ContainerProcessor {
     type Container;
     type NetworkInfo;
@@ -267,6 +986,15 @@ mod tests {
         assert_eq!(normalize_duration_from_ns(7200_000_000_000), "2h");
     }
 
+    #[test]
+    fn test_format_duration_secs() {
+        assert_eq!(format_duration_secs(0), "0s");
+        assert_eq!(format_duration_secs(10), "10s");
+        assert_eq!(format_duration_secs(90), "1m30s");
+        assert_eq!(format_duration_secs(120), "2m");
+        assert_eq!(format_duration_secs(3700), "1h1m40s");
+    }
+
     #[test]
     fn test_normalize_duration() {
         assert_eq!(normalize_duration("30s"), "30s");
@@ -274,6 +1002,142 @@ mod tests {
         assert_eq!(normalize_duration("2m"), "2m");
     }
 
+    #[test]
+    fn test_parse_size_bytes() {
+        assert_eq!(parse_size_bytes("1024"), Some(1024));
+        assert_eq!(parse_size_bytes("64m"), Some(64 * 1024 * 1024));
+        assert_eq!(parse_size_bytes("65536k"), Some(65536 * 1024));
+        assert_eq!(parse_size_bytes("1GB"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_size_bytes("lots"), None);
+    }
+
+    #[test]
+    fn test_format_size_bytes() {
+        assert_eq!(format_size_bytes(512 * 1024 * 1024), "512M");
+        assert_eq!(format_size_bytes(2 * 1024 * 1024 * 1024), "2G");
+        assert_eq!(format_size_bytes(65536000), "64000K");
+        assert_eq!(format_size_bytes(1000), "1000");
+        assert_eq!(format_size_bytes(-1), "-1");
+    }
+
+    #[test]
+    fn test_unpublished_ports() {
+        let published: HashSet<&str> = ["80/tcp"].into();
+        let exposed = ["9090/tcp", "80/tcp", "53/udp", "443/tcp"];
+        assert_eq!(
+            unpublished_ports(exposed, &published),
+            Some(vec![
+                "53/udp".to_string(),
+                "443".to_string(),
+                "9090".to_string()
+            ])
+        );
+        assert_eq!(unpublished_ports(["80/tcp"], &published), None);
+    }
+
+    #[test]
+    fn test_image_platform() {
+        assert_eq!(
+            image_platform("linux", "amd64", "").as_deref(),
+            Some("linux/amd64")
+        );
+        assert_eq!(
+            image_platform("linux", "arm64", "v8").as_deref(),
+            Some("linux/arm64")
+        );
+        assert_eq!(
+            image_platform("linux", "arm", "v7").as_deref(),
+            Some("linux/arm/v7")
+        );
+        assert_eq!(image_platform("", "amd64", ""), None);
+    }
+
+    #[test]
+    fn test_is_cdi_device() {
+        assert!(is_cdi_device("nvidia.com/gpu=all"));
+        assert!(is_cdi_device("vendor.com/device=0"));
+        assert!(!is_cdi_device("/dev/dri/renderD128"));
+        assert!(!is_cdi_device("/dev/sda=x"));
+        assert!(!is_cdi_device("nvidia.com/gpu="));
+    }
+
+    #[test]
+    fn test_device_reservation_serialization() {
+        let devices = vec![
+            DeviceReservation {
+                driver: Some("nvidia".to_string()),
+                count: Some(DeviceCount::All("all".to_string())),
+                capabilities: vec!["gpu".to_string()],
+                ..Default::default()
+            },
+            DeviceReservation {
+                device_ids: Some(vec!["0".to_string(), "3".to_string()]),
+                capabilities: vec!["gpu".to_string(), "utility".to_string()],
+                ..Default::default()
+            },
+        ];
+        let yaml = serde_yaml::to_string(&devices).unwrap();
+        assert!(yaml.contains("count: all"));
+        assert!(yaml.contains("device_ids:\n  - '0'\n  - '3'"));
+
+        let parsed: Vec<DeviceReservation> =
+            serde_yaml::from_str("- count: 2\n  capabilities: [gpu]").unwrap();
+        assert_eq!(parsed[0].count, Some(DeviceCount::Count(2)));
+    }
+
+    #[test]
+    fn test_is_image_id() {
+        let id = "a".repeat(64);
+        assert!(is_image_id(&id));
+        assert!(is_image_id(&format!("sha256:{}", id)));
+        assert!(!is_image_id("nginx:latest"));
+        assert!(!is_image_id(&id[..12]));
+    }
+
+    #[test]
+    fn test_pin_image_digest() {
+        let digests = vec![
+            "nginx@sha256:1111".to_string(),
+            "registry.local:5000/team/app@sha256:2222".to_string(),
+        ];
+
+        assert_eq!(
+            pin_image_digest("nginx:1.25", &digests).as_deref(),
+            Some("nginx:1.25@sha256:1111")
+        );
+        assert_eq!(
+            pin_image_digest("docker.io/library/nginx", &digests).as_deref(),
+            Some("docker.io/library/nginx@sha256:1111")
+        );
+        assert_eq!(
+            pin_image_digest("registry.local:5000/team/app:2.0", &digests).as_deref(),
+            Some("registry.local:5000/team/app:2.0@sha256:2222")
+        );
+        assert_eq!(
+            pin_image_digest("app@sha256:3333", &[]).as_deref(),
+            Some("app@sha256:3333")
+        );
+        assert_eq!(pin_image_digest("local/build:dev", &digests), None);
+    }
+
+    #[test]
+    fn test_filter_endpoint_aliases() {
+        let aliases = vec![
+            "3f4e5d6c7b8a".to_string(),
+            "web".to_string(),
+            "frontend".to_string(),
+            "proxy.internal".to_string(),
+        ];
+        let filtered = filter_endpoint_aliases(aliases, "3f4e5d6c7b8a9f0e1d2c", &["web"]);
+        assert_eq!(
+            filtered,
+            Some(vec!["frontend".to_string(), "proxy.internal".to_string()])
+        );
+
+        let only_generated = vec!["3f4e5d6c7b8a".to_string(), "web".to_string()];
+        assert_eq!(filter_endpoint_aliases(only_generated, "3f4e5d6c7b8a", &["web"]), None);
+    }
+
     #[test]
     fn test_sanitize_service_name() {
         assert_eq!(sanitize_service_name("/my-container"), "my-container");
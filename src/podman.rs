@@ -9,20 +9,73 @@ Contact: contact@olympus-chain.fr
 */
 
 use crate::{
-    filter_system_labels, normalize_duration, sanitize_service_name,
-    security::{filter_sensitive_env_vars, validate_container_id, validate_image_id},
-    AutoComposeError, Deploy, HealthCheck, Logging, NetworkConfig, ResourceLimits, Resources,
-    Result, Service, ServiceNetworks, UlimitConfig,
+    cli::ExternalMode,
+    compose_project,
+    dependencies::{apply_dependencies, link_namespaces, ContainerRelations},
+    docker::ProcessingOptions,
+    expose_entry, filter_endpoint_aliases, filter_system_annotations, filter_system_labels,
+    format_duration_secs, format_size_bytes, image_platform, is_anonymous_volume, is_cdi_device,
+    is_image_id, memswap_limit, normalize_duration, parse_size_bytes, pin_image_digest,
+    projects::ComposeLabels,
+    pruning::{prune_daemon_defaults, prune_image_defaults, DaemonDefaults, ImageDefaults},
+    sanitize_service_name,
+    security::{
+        filter_sensitive_env_vars, validate_container_id, validate_image_id,
+        validate_resource_name,
+    },
+    tmpfs_entry, unpublished_ports, AutoComposeError, BlkioConfig, BlkioThrottle,
+    BlkioWeightDevice, Deploy, HealthCheck, Logging, NetworkConfig, NetworkDefinition,
+    PodmanExtension, ResourceLimits, ResourceReservations, Resources, Result, Service,
+    ServiceNetworks, ServiceVolume, TmpfsOptions, UlimitConfig, VolumeDefinition, VolumeMount,
 };
 use futures::stream::{FuturesUnordered, StreamExt};
 use serde_json::Value;
 use serde_yaml;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::Ipv4Addr;
 use tokio::process::Command;
 
 pub struct PodmanProcessor;
 
+/// CFS period the kernel uses when none is given, in microseconds.
+const DEFAULT_CPU_PERIOD: i64 = 100_000;
+
+/// Options Podman adds to every `--tmpfs` mount.
+const DEFAULT_TMPFS_OPTIONS: [&str; 5] = ["rw", "rprivate", "nosuid", "nodev", "tmpcopyup"];
+
+/// The containers of one pod, gathered while inspecting.
+#[derive(Default)]
+struct PodContainers {
+    infra: Option<Service>,
+    /// Creation time and service name of every other container
+    members: Vec<(String, String)>,
+}
+
+/// Collects the strings of a JSON array such as `Env` or `RepoDigests`.
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Collects the string entries of a JSON object such as `Labels` or `Options`.
+fn string_map(value: &Value) -> HashMap<String, String> {
+    value
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .filter_map(|(key, value)| value.as_str().map(|v| (key.clone(), v.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 impl Default for PodmanProcessor {
     fn default() -> Self {
         Self::new()
@@ -62,6 +115,19 @@ impl PodmanProcessor {
         HashMap<String, Service>,
         HashMap<String, serde_yaml::Value>,
         HashMap<String, serde_yaml::Value>,
+    )> {
+        self.process_containers_parallel_with_options(container_ids, ProcessingOptions::default())
+            .await
+    }
+
+    pub async fn process_containers_parallel_with_options(
+        &self,
+        container_ids: Vec<String>,
+        options: ProcessingOptions,
+    ) -> Result<(
+        HashMap<String, Service>,
+        HashMap<String, serde_yaml::Value>,
+        HashMap<String, serde_yaml::Value>,
     )> {
         let mut inspect_tasks = FuturesUnordered::new();
 
@@ -71,20 +137,66 @@ impl PodmanProcessor {
 
         let mut services = HashMap::new();
         let mut global_networks = HashMap::new();
-        let volumes = HashMap::new();
+        let mut volumes = HashMap::new();
+        let mut network_users: HashMap<String, HashSet<Option<String>>> = HashMap::new();
+        let mut volume_users: HashMap<String, HashSet<Option<String>>> = HashMap::new();
+        let mut relations = Vec::new();
+        let mut pods: HashMap<String, PodContainers> = HashMap::new();
+        let mut images = HashMap::new();
 
         while let Some(result) = inspect_tasks.next().await {
             match result {
                 Ok(inspect_data) => {
                     if let Some(container) = inspect_data.get(0) {
-                        if let Ok((service_name, service, _networks, network_configs)) =
-                            Self::translate_container(container).await
+                        let labels = string_map(&container["Config"]["Labels"]);
+                        let project = compose_project(&labels);
+
+                        if let Ok((service_name, service, networks, network_configs)) =
+                            Self::translate_container(container, &options, &mut images).await
                         {
-                            services.insert(service_name, service);
+                            for net_name in networks {
+                                network_users
+                                    .entry(net_name.clone())
+                                    .or_default()
+                                    .insert(project.clone());
+                                global_networks.entry(net_name).or_insert_with(|| {
+                                    serde_yaml::Value::Mapping(serde_yaml::Mapping::new())
+                                });
+                            }
 
                             for (net_name, net_config) in network_configs {
                                 global_networks.insert(net_name, net_config);
                             }
+
+                            for vol in Self::extract_volume_names(container) {
+                                volume_users
+                                    .entry(vol.clone())
+                                    .or_default()
+                                    .insert(project.clone());
+                                volumes.entry(vol).or_insert_with(|| {
+                                    serde_yaml::Value::Mapping(serde_yaml::Mapping::new())
+                                });
+                            }
+
+                            let pod = container["Pod"]
+                                .as_str()
+                                .filter(|pod| options.include_pods && !pod.is_empty())
+                                .map(|pod| pods.entry(pod.to_string()).or_default());
+                            if let Some(pod) = pod {
+                                // The infra container only holds the pod namespaces, a
+                                // member takes over its ports and networks
+                                if container["IsInfra"].as_bool() == Some(true) {
+                                    pod.infra = Some(service);
+                                    continue;
+                                }
+                                let created = container["Created"].as_str().unwrap_or("");
+                                pod.members.push((created.to_string(), service_name.clone()));
+                            }
+
+                            let mut container_relations = Self::extract_relations(container);
+                            container_relations.service = service_name.clone();
+                            relations.push(container_relations);
+                            services.insert(service_name, service);
                         }
                     }
                 }
@@ -92,9 +204,334 @@ impl PodmanProcessor {
             }
         }
 
+        for (pod_id, pod) in pods {
+            Self::assemble_pod(&pod_id, pod, &mut services).await;
+        }
+
+        apply_dependencies(&mut services, &relations);
+        link_namespaces(&mut services, &relations);
+
+        if !options.keep_daemon_defaults {
+            let daemon_defaults = Self::daemon_defaults().await;
+            for service in services.values_mut() {
+                prune_daemon_defaults(service, &daemon_defaults);
+            }
+        }
+
+        let no_users = HashSet::new();
+
+        for (name, definition) in global_networks.iter_mut() {
+            let users = network_users.get(name).unwrap_or(&no_users);
+
+            // Predefined networks cannot be redefined by compose, only referenced
+            let owner = if Self::is_predefined_network(name) {
+                None
+            } else {
+                match Self::inspect_network(name).await {
+                    Ok(network) => compose_project(&string_map(&network["Labels"])),
+                    Err(e) => {
+                        eprintln!("Warning: could not inspect network {}: {}", name, e);
+                        if options.external == ExternalMode::Always {
+                            *definition = serde_yaml::to_value(NetworkDefinition::external(name))?;
+                        }
+                        continue;
+                    }
+                }
+            };
+
+            if options.external.applies(owner.as_deref(), users) {
+                *definition = serde_yaml::to_value(NetworkDefinition::external(name))?;
+            }
+        }
+
+        for (name, definition) in volumes.iter_mut() {
+            let users = volume_users.get(name).unwrap_or(&no_users);
+
+            match Self::inspect_volume(name).await {
+                Ok(volume) => {
+                    let owner = compose_project(&string_map(&volume["Labels"]));
+                    *definition = if options.external.applies(owner.as_deref(), users) {
+                        serde_yaml::to_value(VolumeDefinition::external(name))?
+                    } else {
+                        serde_yaml::to_value(Self::volume_definition(&volume))?
+                    };
+                }
+                Err(e) => {
+                    eprintln!("Warning: could not inspect volume {}: {}", name, e);
+                    if options.external == ExternalMode::Always {
+                        *definition = serde_yaml::to_value(VolumeDefinition::external(name))?;
+                    }
+                }
+            }
+        }
+
         Ok((services, global_networks, volumes))
     }
 
+    fn extract_relations(container: &Value) -> ContainerRelations {
+        let mut identifiers: Vec<String> = [&container["Name"], &container["Config"]["Hostname"]]
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect();
+        if let Some(networks) = container["NetworkSettings"]["Networks"].as_object() {
+            for endpoint in networks.values() {
+                identifiers.extend(string_list(&endpoint["Aliases"]));
+            }
+        }
+
+        ContainerRelations {
+            service: String::new(),
+            id: container["Id"].as_str().unwrap_or_default().to_string(),
+            identifiers,
+            network_container: container["HostConfig"]["NetworkMode"]
+                .as_str()
+                .and_then(|mode| mode.strip_prefix("container:"))
+                .map(|target| target.to_string()),
+            volumes_from: string_list(&container["HostConfig"]["VolumesFrom"]),
+            links: string_list(&container["HostConfig"]["Links"]),
+            env_values: string_list(&container["Config"]["Env"])
+                .into_iter()
+                .filter_map(|e| e.split_once('=').map(|(_, value)| value.to_string()))
+                .collect(),
+            started_at: container["State"]["StartedAt"].as_str().map(|s| s.to_string()),
+            completed: Self::is_completed_job(container),
+            // The container config already includes the image's healthcheck
+            healthcheck: string_list(&container["Config"]["Healthcheck"]["Test"])
+                .first()
+                .is_some_and(|kind| kind != "NONE"),
+        }
+    }
+
+    /// A one-shot job: exited cleanly and not brought back by a restart policy.
+    fn is_completed_job(container: &Value) -> bool {
+        let exited_cleanly = container["State"]["Status"].as_str() == Some("exited")
+            && container["State"]["ExitCode"].as_i64() == Some(0);
+        let restarts = matches!(
+            container["HostConfig"]["RestartPolicy"]["Name"].as_str(),
+            Some("always" | "unless-stopped")
+        );
+
+        exited_cleanly && !restarts
+    }
+
+    async fn daemon_defaults() -> DaemonDefaults {
+        let output = Command::new("podman")
+            .args(["info", "--format", "json"])
+            .output()
+            .await;
+
+        match output {
+            Ok(output) if output.status.success() => {
+                match serde_json::from_slice::<Value>(&output.stdout) {
+                    Ok(info) => return Self::parse_daemon_defaults(&info),
+                    Err(e) => eprintln!("Warning: could not parse podman info: {}", e),
+                }
+            }
+            Ok(output) => eprintln!(
+                "Warning: could not query podman info: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Err(e) => eprintln!("Warning: could not query podman info: {}", e),
+        }
+        DaemonDefaults::podman()
+    }
+
+    fn parse_daemon_defaults(info: &Value) -> DaemonDefaults {
+        let host = &info["host"];
+        let text = |value: &Value| value.as_str().filter(|s| !s.is_empty()).map(|s| s.to_string());
+        let mut defaults = DaemonDefaults::podman();
+
+        if let Some(driver) = text(&host["logDriver"]) {
+            defaults.log_driver = driver;
+        }
+        defaults.default_runtime = text(&host["ociRuntime"]["name"]);
+        if text(&host["cgroupVersion"]).as_deref() == Some("v1") {
+            defaults.cgroupns_mode = Some("host".to_string());
+        }
+
+        let rootless = host["security"]["rootless"].as_bool() == Some(true);
+        // The systemd cgroup manager puts containers under a slice of its own
+        if text(&host["cgroupManager"]).as_deref() == Some("systemd") {
+            let slice = if rootless { "user.slice" } else { "machine.slice" };
+            defaults.cgroup_parent = Some(slice.to_string());
+        }
+
+        // Rootless containers get the user-mode network stack by default
+        if rootless {
+            match text(&host["rootlessNetworkCmd"]) {
+                Some(cmd) => defaults.network_modes.push(cmd),
+                None => defaults
+                    .network_modes
+                    .extend(["slirp4netns".to_string(), "pasta".to_string()]),
+            }
+        }
+        defaults
+    }
+
+    async fn inspect_pod(id: &str) -> Result<Value> {
+        // Validate pod ID to prevent command injection
+        let safe_id = validate_resource_name(id)?;
+
+        let output = Command::new("podman")
+            .args(["pod", "inspect", safe_id])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(AutoComposeError::PodmanCommand(format!(
+                "Failed to inspect pod {}: {}",
+                safe_id,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        // Podman 5 wraps the pod in an array, earlier releases do not
+        let inspect_data: Value = serde_json::from_slice(&output.stdout)?;
+        Ok(match inspect_data {
+            Value::Array(mut pods) if !pods.is_empty() => pods.swap_remove(0),
+            pod => pod,
+        })
+    }
+
+    async fn assemble_pod(
+        pod_id: &str,
+        pod: PodContainers,
+        services: &mut HashMap<String, Service>,
+    ) {
+        let pod_info = Self::inspect_pod(pod_id).await.unwrap_or_else(|e| {
+            eprintln!("Warning: could not inspect pod {}: {}", pod_id, e);
+            Value::Null
+        });
+        let extension = PodmanExtension {
+            pod: pod_info["Name"].as_str().map(|name| name.to_string()),
+            pod_labels: filter_system_labels(string_map(&pod_info["Labels"])),
+        };
+
+        Self::share_pod_namespace(pod, extension, services);
+    }
+
+    /// Rebuilds a pod as services sharing the network namespace of its first
+    /// member, which takes over the ports and networks of the infra container.
+    fn share_pod_namespace(
+        pod: PodContainers,
+        extension: PodmanExtension,
+        services: &mut HashMap<String, Service>,
+    ) {
+        let mut infra = pod.infra;
+        let mut members = pod.members;
+        members.sort();
+        let Some((_, first)) = members.first().cloned() else {
+            return;
+        };
+
+        for (_, member) in &members {
+            let Some(service) = services.get_mut(member) else {
+                continue;
+            };
+            service.x_podman = Some(extension.clone());
+
+            if *member == first {
+                service.network_mode = None;
+                if let Some(infra) = infra.take() {
+                    service.ports = Self::pod_ports(service.ports.take(), infra.ports);
+                    service.networks = infra.networks;
+                    service.network_mode = infra.network_mode;
+                }
+            } else {
+                service.network_mode = Some(format!("service:{}", first));
+                service.networks = None;
+                service.ports = None;
+            }
+        }
+    }
+
+    fn pod_ports(own: Option<Vec<String>>, infra: Option<Vec<String>>) -> Option<Vec<String>> {
+        let mut ports: Vec<String> = own.into_iter().chain(infra).flatten().collect();
+        ports.sort();
+        ports.dedup();
+        (!ports.is_empty()).then_some(ports)
+    }
+
+    fn is_predefined_network(name: &str) -> bool {
+        matches!(name, "podman" | "bridge" | "host" | "none")
+    }
+
+    async fn inspect_network(name: &str) -> Result<Value> {
+        // Validate network name to prevent command injection
+        let safe_name = validate_resource_name(name)?;
+
+        let output = Command::new("podman")
+            .args(["network", "inspect", safe_name])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(AutoComposeError::PodmanCommand(format!(
+                "Failed to inspect network {}: {}",
+                safe_name,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let inspect_data: Value = serde_json::from_slice(&output.stdout)?;
+        Ok(inspect_data.get(0).cloned().unwrap_or(Value::Null))
+    }
+
+    async fn inspect_volume(name: &str) -> Result<Value> {
+        // Validate volume name to prevent command injection
+        let safe_name = validate_resource_name(name)?;
+
+        let output = Command::new("podman")
+            .args(["volume", "inspect", safe_name])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(AutoComposeError::PodmanCommand(format!(
+                "Failed to inspect volume {}: {}",
+                safe_name,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        let inspect_data: Value = serde_json::from_slice(&output.stdout)?;
+        Ok(inspect_data.get(0).cloned().unwrap_or(Value::Null))
+    }
+
+    fn volume_definition(volume: &Value) -> VolumeDefinition {
+        let driver_opts = string_map(&volume["Options"]);
+
+        VolumeDefinition {
+            driver: volume["Driver"]
+                .as_str()
+                .filter(|d| !d.is_empty())
+                .map(|d| d.to_string()),
+            driver_opts: if driver_opts.is_empty() {
+                None
+            } else {
+                Some(driver_opts)
+            },
+            labels: filter_system_labels(string_map(&volume["Labels"])),
+            name: volume["Name"].as_str().map(|n| n.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn extract_volume_names(container: &Value) -> Vec<String> {
+        container["Mounts"]
+            .as_array()
+            .map(|mounts| {
+                mounts
+                    .iter()
+                    .filter(|mount| mount["Type"].as_str() == Some("volume"))
+                    .filter_map(|mount| mount["Name"].as_str())
+                    .filter(|name| !name.is_empty() && !is_anonymous_volume(name))
+                    .map(|name| name.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     async fn inspect_container(id: String) -> Result<Value> {
         // Validate container ID to prevent command injection
         let safe_id = validate_container_id(&id)?;
@@ -116,9 +553,9 @@ impl PodmanProcessor {
         Ok(inspect_data)
     }
 
-    async fn get_image_repo(image_id: &str) -> Option<String> {
+    async fn inspect_image(image: &str) -> Option<Value> {
         // Validate image ID to prevent command injection
-        let safe_image_id = validate_image_id(image_id).ok()?;
+        let safe_image_id = validate_image_id(image).ok()?;
 
         let output = Command::new("podman")
             .args(["image", "inspect", safe_image_id])
@@ -131,7 +568,24 @@ impl PodmanProcessor {
         }
 
         let image_info: Value = serde_json::from_slice(&output.stdout).ok()?;
-        let repo_tags = image_info.get(0)?.get("RepoTags")?;
+        image_info.get(0).cloned()
+    }
+
+    /// Inspects each image once, however many containers run it.
+    async fn cached_image(
+        image: &str,
+        images: &mut HashMap<String, Option<Value>>,
+    ) -> Option<Value> {
+        if let Some(info) = images.get(image) {
+            return info.clone();
+        }
+        let info = Self::inspect_image(image).await;
+        images.insert(image.to_string(), info.clone());
+        info
+    }
+
+    fn image_repo(image_info: &Value) -> Option<String> {
+        let repo_tags = image_info.get("RepoTags")?;
 
         if let Some(arr) = repo_tags.as_array() {
             if let Some(first) = arr.first()?.as_str() {
@@ -141,6 +595,34 @@ impl PodmanProcessor {
         None
     }
 
+    fn image_defaults(image: &Value) -> ImageDefaults {
+        let config = &image["Config"];
+        let text = |value: &Value| value.as_str().filter(|s| !s.is_empty()).map(|s| s.to_string());
+        let args = |value: &Value| value.as_array().map(|_| string_list(value));
+        let labels = if image["Labels"].is_object() {
+            &image["Labels"]
+        } else {
+            &config["Labels"]
+        };
+
+        ImageDefaults {
+            env: ImageDefaults::parse_env(string_list(&config["Env"]).iter().map(|e| e.as_str())),
+            cmd: args(&config["Cmd"]),
+            entrypoint: args(&config["Entrypoint"]),
+            working_dir: text(&config["WorkingDir"]),
+            user: text(&config["User"]),
+            healthcheck: Self::health_check(&image["Healthcheck"]),
+            labels: string_map(labels),
+            stop_signal: text(&config["StopSignal"]),
+            exposed_ports: config["ExposedPorts"]
+                .as_object()
+                .into_iter()
+                .flat_map(|ports| ports.keys())
+                .map(|port| expose_entry(port))
+                .collect(),
+        }
+    }
+
     fn compute_subnet(gateway: &str, prefix_len: u64) -> Option<String> {
         if prefix_len > 32 {
             return None;
@@ -159,6 +641,8 @@ impl PodmanProcessor {
 
     async fn translate_container(
         container: &Value,
+        options: &ProcessingOptions,
+        images: &mut HashMap<String, Option<Value>>,
     ) -> Result<(
         String,
         Service,
@@ -171,14 +655,36 @@ impl PodmanProcessor {
             .trim_start_matches('/');
         let service_name = sanitize_service_name(name);
 
-        let mut image = container["Image"].as_str().unwrap_or("unknown").to_string();
+        let image_id = container["Image"].as_str().unwrap_or("unknown");
+        let mut image = image_id.to_string();
+        // The image is always looked up for its platform
+        let image_info = Self::cached_image(image_id, images).await;
+        let platform = image_info.as_ref().and_then(|info| {
+            let text = |key: &str| info[key].as_str().unwrap_or_default();
+            image_platform(text("Os"), text("Architecture"), text("Variant"))
+        });
 
-        if image.len() == 64 && image.chars().all(|c| c.is_ascii_hexdigit()) {
-            if let Some(repo) = Self::get_image_repo(&image).await {
+        if is_image_id(&image) {
+            if let Some(repo) = image_info.as_ref().and_then(Self::image_repo) {
                 image = repo;
             }
         }
 
+        if options.pin_digests {
+            let digests = image_info
+                .as_ref()
+                .map(|info| string_list(&info["RepoDigests"]))
+                .unwrap_or_default();
+            match pin_image_digest(&image, &digests) {
+                Some(pinned) => image = pinned,
+                None => eprintln!(
+                    "Warning: not pinning {} for service {}: the image has no registry digest \
+                     (built locally or never pushed)",
+                    image, service_name
+                ),
+            }
+        }
+
         let hostname = container["Config"]["Hostname"]
             .as_str()
             .map(|h| h.to_string())
@@ -207,10 +713,16 @@ impl PodmanProcessor {
         });
 
         let ports = Self::extract_ports(container);
+        let expose = Self::extract_expose(container);
 
         let volumes = Self::extract_volumes(container);
+        let tmpfs = Self::extract_tmpfs(container);
 
-        let (networks, network_configs) = Self::extract_networks(container);
+        let compose = ComposeLabels::from_labels(&string_map(&container["Config"]["Labels"]));
+        // Compose aliases every container with its service name
+        let mut own_names = vec![name, service_name.as_str()];
+        own_names.extend(compose.as_ref().map(|c| c.service.as_str()));
+        let (networks, network_configs) = Self::extract_networks(container, &own_names);
 
         let network_mode = container["HostConfig"]["NetworkMode"]
             .as_str()
@@ -309,7 +821,7 @@ impl PodmanProcessor {
 
         let deploy = Self::extract_deploy_config(container);
 
-        let healthcheck = Self::extract_healthcheck(container);
+        let healthcheck = Self::health_check(&container["Config"]["Healthcheck"]);
 
         let labels = container["Config"]["Labels"]
             .as_object()
@@ -347,6 +859,78 @@ impl PodmanProcessor {
         let tty = container["Config"]["Tty"].as_bool();
         let stdin_open = container["Config"]["OpenStdin"].as_bool();
 
+        // Namespace, cgroup and runtime settings; defaults are pruned later.
+        // Pod members join the namespaces of the pod's infra container.
+        let in_pod = container["Pod"].as_str().is_some_and(|pod| !pod.is_empty());
+        let mode = |key: &str| {
+            container["HostConfig"][key]
+                .as_str()
+                .filter(|m| !m.is_empty())
+                .filter(|m| !(in_pod && m.starts_with("container:")))
+                .map(|m| m.to_string())
+        };
+        let pid = mode("PidMode");
+        let ipc = mode("IpcMode");
+        let uts = mode("UTSMode");
+        let userns_mode = mode("UsernsMode");
+        let cgroup = mode("CgroupMode");
+        let cgroup_parent = mode("CgroupParent");
+        let isolation = mode("Isolation").filter(|i| i != "default");
+        // HostConfig.Runtime is always "oci", the runtime itself is named here
+        let runtime = container["OCIRuntime"]
+            .as_str()
+            .filter(|r| !r.is_empty())
+            .map(|r| r.to_string());
+
+        // Resource controls the engine leaves at zero when they are unset
+        let host_config = &container["HostConfig"];
+        let positive = |key: &str| host_config[key].as_i64().filter(|v| *v > 0);
+        let cpu_shares = positive("CpuShares");
+        // A quota over the default period is what `--cpus` sets, and is
+        // exported as `deploy.resources.limits.cpus` instead
+        let (cpu_period, cpu_quota) = match (positive("CpuPeriod"), positive("CpuQuota")) {
+            (Some(DEFAULT_CPU_PERIOD), Some(_)) => (None, None),
+            (period, quota) => (period, quota),
+        };
+        let cpuset = host_config["CpusetCpus"]
+            .as_str()
+            .filter(|c| !c.is_empty())
+            .map(|c| c.to_string());
+        let mem_swappiness = host_config["MemorySwappiness"].as_i64().filter(|s| *s >= 0);
+        let memswap_limit = memswap_limit(
+            host_config["Memory"].as_i64().unwrap_or_default(),
+            host_config["MemorySwap"].as_i64().unwrap_or_default(),
+        );
+        let pids_limit = positive("PidsLimit");
+        let oom_kill_disable = host_config["OomKillDisable"].as_bool().filter(|d| *d);
+        let oom_score_adj = host_config["OomScoreAdj"].as_i64().filter(|a| *a != 0);
+        let shm_size = positive("ShmSize").map(format_size_bytes);
+        let blkio_config = Self::extract_blkio_config(host_config);
+
+        let read_only = host_config["ReadonlyRootfs"].as_bool().filter(|r| *r);
+        // Older releases report the signal number
+        let stop_signal = match &container["Config"]["StopSignal"] {
+            Value::Number(number) if number.as_u64() == Some(15) => Some("SIGTERM".to_string()),
+            Value::Number(number) => Some(number.to_string()),
+            signal => signal
+                .as_str()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string()),
+        };
+        let stop_grace_period = container["Config"]["StopTimeout"]
+            .as_i64()
+            .map(format_duration_secs);
+        let group_add = Some(string_list(&host_config["GroupAdd"])).filter(|g| !g.is_empty());
+        let domainname = container["Config"]["Domainname"]
+            .as_str()
+            .filter(|d| !d.is_empty())
+            .map(|d| d.to_string());
+        let storage_opt = Some(string_map(&host_config["StorageOpt"])).filter(|o| !o.is_empty());
+        let device_cgroup_rules =
+            Some(string_list(&host_config["DeviceCgroupRules"])).filter(|r| !r.is_empty());
+        let annotations =
+            filter_system_annotations(string_map(&container["Config"]["Annotations"]));
+
         let user = container["Config"]["User"]
             .as_str()
             .filter(|u| !u.is_empty())
@@ -383,13 +967,16 @@ impl PodmanProcessor {
             }
         });
 
-        let service = Service {
+        let mut service = Service {
             image,
+            platform,
             container_name: Some(service_name.clone()),
             hostname,
             environment,
             ports,
+            expose,
             volumes,
+            tmpfs,
             networks,
             network_mode,
             dns,
@@ -399,6 +986,25 @@ impl PodmanProcessor {
             cap_add,
             cap_drop,
             security_opt,
+            pid,
+            ipc,
+            uts,
+            userns_mode,
+            cgroup,
+            cgroup_parent,
+            isolation,
+            runtime,
+            cpu_shares,
+            cpu_period,
+            cpu_quota,
+            cpuset,
+            mem_swappiness,
+            memswap_limit,
+            pids_limit,
+            oom_kill_disable,
+            oom_score_adj,
+            shm_size,
+            blkio_config,
             deploy,
             healthcheck,
             labels,
@@ -414,10 +1020,34 @@ impl PodmanProcessor {
             privileged,
             tty,
             stdin_open,
-            depends_on: None, // Podman doesn't provide dependency info
+            read_only,
+            stop_signal,
+            stop_grace_period,
+            group_add,
+            domainname,
+            mac_address: None,
+            storage_opt,
+            device_cgroup_rules,
+            annotations,
+            secrets: None,
+            configs: None,
+            depends_on: None, // Inferred once every container has been inspected
+            x_podman: None,
+            compose,
         };
 
-        Ok((service_name, service, vec![], network_configs))
+        if !options.keep_image_defaults {
+            if let Some(info) = &image_info {
+                prune_image_defaults(&mut service, &Self::image_defaults(info));
+            }
+        }
+
+        let network_names = container["NetworkSettings"]["Networks"]
+            .as_object()
+            .map(|networks| networks.keys().cloned().collect())
+            .unwrap_or_default();
+
+        Ok((service_name, service, network_names, network_configs))
     }
 
     fn extract_ports(container: &Value) -> Option<Vec<String>> {
@@ -453,19 +1083,146 @@ impl PodmanProcessor {
             })
     }
 
-    fn extract_volumes(container: &Value) -> Option<Vec<String>> {
+    /// Exposed ports without a host binding. Podman lists them in
+    /// `NetworkSettings.Ports` too, with no bindings.
+    fn extract_expose(container: &Value) -> Option<Vec<String>> {
+        let ports = container["NetworkSettings"]["Ports"].as_object();
+        let published: HashSet<&str> = ports
+            .into_iter()
+            .flatten()
+            .filter(|(_, bindings)| bindings.as_array().is_some_and(|b| !b.is_empty()))
+            .map(|(port, _)| port.as_str())
+            .collect();
+        let exposed = container["Config"]["ExposedPorts"]
+            .as_object()
+            .into_iter()
+            .chain(ports)
+            .flat_map(|ports| ports.keys())
+            .map(|port| port.as_str());
+        unpublished_ports(exposed, &published)
+    }
+
+    /// `--tmpfs` mounts, which are only listed in `HostConfig.Tmpfs`, and tmpfs
+    /// mounts without options, which like Docker's go in the short list.
+    fn extract_tmpfs(container: &Value) -> Option<Vec<String>> {
+        let mounts = container["Mounts"].as_array().into_iter().flatten();
+        let (plain, mounted): (Vec<&Value>, Vec<&Value>) =
+            mounts.partition(|mount| Self::is_plain_tmpfs(mount));
+        let mounted: HashSet<&str> = mounted
+            .iter()
+            .filter_map(|mount| mount["Destination"].as_str())
+            .collect();
+
+        let flags = string_map(&container["HostConfig"]["Tmpfs"]);
+        let mut tmpfs: Vec<String> = flags
+            .iter()
+            .filter(|(target, _)| !mounted.contains(target.as_str()))
+            .map(|(target, options)| {
+                let options: Vec<&str> = options
+                    .split(',')
+                    .filter(|o| !o.is_empty() && !DEFAULT_TMPFS_OPTIONS.contains(o))
+                    .collect();
+                tmpfs_entry(target, &options.join(","))
+            })
+            .collect();
+        tmpfs.extend(
+            plain
+                .iter()
+                .filter_map(|mount| mount["Destination"].as_str())
+                .filter(|target| !flags.contains_key(*target))
+                .map(|target| target.to_string()),
+        );
+        tmpfs.sort();
+        (!tmpfs.is_empty()).then_some(tmpfs)
+    }
+
+    /// Size and mode of a tmpfs mount, from its mount options.
+    fn tmpfs_options(mount: &Value) -> Option<TmpfsOptions> {
+        let options: Vec<&str> = mount["Options"]
+            .as_array()
+            .map(|opts| opts.iter().filter_map(|o| o.as_str()).collect())
+            .unwrap_or_default();
+        let size = options
+            .iter()
+            .find_map(|o| o.strip_prefix("size="))
+            .and_then(parse_size_bytes);
+        let mode = options
+            .iter()
+            .find_map(|o| o.strip_prefix("mode="))
+            .and_then(|m| m.parse::<u32>().ok());
+        (size.is_some() || mode.is_some()).then_some(TmpfsOptions { size, mode })
+    }
+
+    /// A writable tmpfs mount with neither size nor mode.
+    fn is_plain_tmpfs(mount: &Value) -> bool {
+        mount["Type"].as_str() == Some("tmpfs")
+            && mount["RW"].as_bool() != Some(false)
+            && Self::tmpfs_options(mount).is_none()
+    }
+
+    fn extract_volumes(container: &Value) -> Option<Vec<ServiceVolume>> {
         container["Mounts"].as_array().and_then(|mounts| {
-            let volume_mappings: Vec<String> = mounts
+            let volume_mappings: Vec<ServiceVolume> = mounts
                 .iter()
                 .filter_map(|mount| {
-                    let src = mount["Source"].as_str()?;
                     let dst = mount["Destination"].as_str()?;
                     let mount_type = mount["Type"].as_str().unwrap_or("bind");
+                    let read_only = mount["RW"].as_bool() == Some(false);
+                    let mount_options: Vec<&str> = mount["Options"]
+                        .as_array()
+                        .map(|opts| opts.iter().filter_map(|o| o.as_str()).collect())
+                        .unwrap_or_default();
+
+                    if mount_type == "tmpfs" {
+                        // Plain tmpfs mounts go in the short `tmpfs` list
+                        if Self::is_plain_tmpfs(mount) {
+                            return None;
+                        }
+                        return Some(ServiceVolume::Long(VolumeMount {
+                            mount_type: "tmpfs".to_string(),
+                            source: None,
+                            target: dst.to_string(),
+                            read_only: read_only.then_some(true),
+                            bind: None,
+                            volume: None,
+                            tmpfs: Self::tmpfs_options(mount),
+                        }));
+                    }
 
-                    Some(match mount_type {
-                        "tmpfs" => format!("tmpfs:{}", dst),
-                        _ => format!("{}:{}", src, dst),
-                    })
+                    let src = match mount_type {
+                        "volume" => mount["Name"].as_str().or_else(|| mount["Source"].as_str())?,
+                        _ => mount["Source"].as_str()?,
+                    };
+
+                    // Anonymous volumes only carry their container path
+                    if mount_type == "volume" && is_anonymous_volume(src) {
+                        return Some(ServiceVolume::Short(dst.to_string()));
+                    }
+
+                    let mut options = Vec::new();
+                    if read_only {
+                        options.push("ro");
+                    }
+                    if mount_type == "bind" {
+                        if let Some(propagation) = mount["Propagation"]
+                            .as_str()
+                            .filter(|p| !p.is_empty() && *p != "rprivate")
+                        {
+                            options.push(propagation);
+                        }
+                        options.extend(
+                            mount_options
+                                .iter()
+                                .filter(|o| **o == "z" || **o == "Z")
+                                .copied(),
+                        );
+                    }
+
+                    Some(ServiceVolume::Short(if options.is_empty() {
+                        format!("{}:{}", src, dst)
+                    } else {
+                        format!("{}:{}:{}", src, dst, options.join(","))
+                    }))
                 })
                 .collect();
             if volume_mappings.is_empty() {
@@ -478,7 +1235,13 @@ impl PodmanProcessor {
 
     fn extract_networks(
         container: &Value,
+        own_names: &[&str],
     ) -> (Option<ServiceNetworks>, HashMap<String, serde_yaml::Value>) {
+        let container_id = container["Id"].as_str().unwrap_or_default();
+        let non_empty =
+            |value: &Value| value.as_str().filter(|v| !v.is_empty()).map(|v| v.to_string());
+        let non_empty_list = |value: &Value| Some(string_list(value)).filter(|l| !l.is_empty());
+
         let mut network_configs = HashMap::new();
         let mut service_networks = HashMap::new();
 
@@ -518,22 +1281,35 @@ impl PodmanProcessor {
                     }
                 }
 
-                if let Some(ip) = net_info["IPAddress"].as_str() {
-                    if !ip.is_empty() {
-                        service_networks.insert(
-                            net_name.clone(),
-                            NetworkConfig {
-                                ipv4_address: Some(ip.to_string()),
-                                ipv6_address: None,
-                            },
-                        );
-                    }
-                }
+                let driver_opts = string_map(&net_info["DriverOpts"]);
+
+                // Only requested addresses are in IPAMConfig. Podman gives every
+                // endpoint a random MAC and does not say which ones were asked for.
+                let ipam = &net_info["IPAMConfig"];
+                let config = NetworkConfig {
+                    aliases: non_empty_list(&net_info["Aliases"])
+                        .and_then(|a| filter_endpoint_aliases(a, container_id, own_names)),
+                    ipv4_address: non_empty(&ipam["IPv4Address"]),
+                    ipv6_address: non_empty(&ipam["IPv6Address"]),
+                    link_local_ips: non_empty_list(&ipam["LinkLocalIPs"]),
+                    mac_address: None,
+                    driver_opts: if driver_opts.is_empty() {
+                        None
+                    } else {
+                        Some(driver_opts)
+                    },
+                    gw_priority: net_info["GwPriority"].as_i64().filter(|p| *p != 0),
+                };
+
+                service_networks.insert(net_name.clone(), config);
             }
         }
 
-        let networks = if service_networks.is_empty() {
-            None
+        let networks = if service_networks.values().all(NetworkConfig::is_empty) {
+            let mut names: Vec<String> = service_networks.into_keys().collect();
+            names.sort();
+            // The default network needs no mention
+            (!names.is_empty() && names != ["podman"]).then_some(ServiceNetworks::Simple(names))
         } else {
             Some(ServiceNetworks::Advanced(service_networks))
         };
@@ -565,43 +1341,84 @@ impl PodmanProcessor {
     }
 
     fn extract_deploy_config(container: &Value) -> Option<Deploy> {
-        let mut has_resources = false;
-        let mut memory = None;
-        let mut cpus = None;
-
-        if let Some(mem) = container["HostConfig"]["Memory"].as_u64() {
-            if mem > 0 {
-                let memory_mb = mem / (1024 * 1024);
-                memory = Some(format!("{}M", memory_mb));
-                has_resources = true;
-            }
-        }
+        let host_config = &container["HostConfig"];
+        let positive = |key: &str| host_config[key].as_i64().filter(|v| *v > 0);
 
-        if let (Some(cpu_quota), Some(cpu_period)) = (
-            container["HostConfig"]["CpuQuota"].as_i64(),
-            container["HostConfig"]["CpuPeriod"].as_i64(),
+        let cpus = match (
+            positive("NanoCpus"),
+            positive("CpuQuota"),
+            positive("CpuPeriod"),
         ) {
-            if cpu_quota > 0 && cpu_period > 0 {
-                let cpu_value = cpu_quota as f64 / cpu_period as f64;
-                cpus = Some(format!("{:.2}", cpu_value));
-                has_resources = true;
+            (Some(nano_cpus), _, _) => Some(nano_cpus as f64 / 1e9),
+            (None, Some(quota), Some(DEFAULT_CPU_PERIOD)) => {
+                Some(quota as f64 / DEFAULT_CPU_PERIOD as f64)
             }
-        }
+            _ => None,
+        };
+        let limits = ResourceLimits {
+            cpus: cpus.map(|cpus| format!("{:.2}", cpus)),
+            memory: positive("Memory").map(format_size_bytes),
+        };
+        let reservations = ResourceReservations {
+            memory: positive("MemoryReservation").map(format_size_bytes),
+            ..Default::default()
+        };
 
-        if has_resources {
-            Some(Deploy {
-                resources: Some(Resources {
-                    limits: Some(ResourceLimits { memory, cpus }),
-                }),
-                placement: None,
+        Resources::new(limits, reservations).map(|resources| Deploy {
+            resources: Some(resources),
+            ..Default::default()
+        })
+    }
+
+    fn extract_blkio_config(host_config: &Value) -> Option<BlkioConfig> {
+        let devices = |key: &str| {
+            host_config[key]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|device| {
+                    let path = device["Path"].as_str().filter(|p| !p.is_empty())?;
+                    Some((path.to_string(), device))
+                })
+                .collect::<Vec<_>>()
+        };
+        let throttles = |key: &str| {
+            let throttles: Vec<_> = devices(key)
+                .into_iter()
+                .filter_map(|(path, device)| {
+                    let rate = device["Rate"].as_u64().filter(|r| *r > 0)?;
+                    Some(BlkioThrottle { path, rate })
+                })
+                .collect();
+            (!throttles.is_empty()).then_some(throttles)
+        };
+        let weight_device: Vec<_> = devices("BlkioWeightDevice")
+            .into_iter()
+            .filter_map(|(path, device)| {
+                let weight = device["Weight"].as_u64().filter(|w| *w > 0)?;
+                Some(BlkioWeightDevice {
+                    path,
+                    weight: weight as u16,
+                })
             })
-        } else {
-            None
-        }
+            .collect();
+
+        let blkio = BlkioConfig {
+            weight: host_config["BlkioWeight"]
+                .as_u64()
+                .filter(|w| *w > 0)
+                .map(|w| w as u16),
+            weight_device: (!weight_device.is_empty()).then_some(weight_device),
+            device_read_bps: throttles("BlkioDeviceReadBps"),
+            device_write_bps: throttles("BlkioDeviceWriteBps"),
+            device_read_iops: throttles("BlkioDeviceReadIOps"),
+            device_write_iops: throttles("BlkioDeviceWriteIOps"),
+        };
+        (!blkio.is_empty()).then_some(blkio)
     }
 
-    fn extract_healthcheck(container: &Value) -> Option<HealthCheck> {
-        container["Config"]["Healthcheck"]
+    fn health_check(healthcheck: &Value) -> Option<HealthCheck> {
+        healthcheck
             .as_object()
             .and_then(|hc| {
                 let test = hc
@@ -668,6 +1485,10 @@ impl PodmanProcessor {
                     .iter()
                     .filter_map(|d| {
                         let host_path = d["PathOnHost"].as_str()?;
+                        // CDI devices (`--device nvidia.com/gpu=all`, `--gpus`) keep their name
+                        if is_cdi_device(host_path) {
+                            return Some(host_path.to_string());
+                        }
                         let container_path = d["PathInContainer"].as_str()?;
                         Some(format!("{}:{}", host_path, container_path))
                     })
@@ -701,3 +1522,270 @@ impl PodmanProcessor {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_volumes_and_names() {
+        let container = serde_json::json!({
+            "Mounts": [
+                { "Type": "volume", "Name": "pgdata",
+                  "Source": "/var/lib/containers/storage/volumes/pgdata/_data",
+                  "Destination": "/var/lib/postgresql/data", "RW": true, "Propagation": "rprivate" },
+                { "Type": "volume", "Name": "f".repeat(64), "Destination": "/cache", "RW": true },
+                { "Type": "bind", "Source": "/srv/conf", "Destination": "/conf",
+                  "Options": ["rbind", "Z"], "RW": false, "Propagation": "rprivate" }
+            ]
+        });
+
+        let volumes = PodmanProcessor::extract_volumes(&container).unwrap();
+        let short: Vec<&str> = volumes
+            .iter()
+            .filter_map(|v| match v {
+                ServiceVolume::Short(spec) => Some(spec.as_str()),
+                ServiceVolume::Long(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            short,
+            vec!["pgdata:/var/lib/postgresql/data", "/cache", "/srv/conf:/conf:ro,Z"]
+        );
+        assert_eq!(
+            PodmanProcessor::extract_volume_names(&container),
+            vec!["pgdata".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_extract_cdi_devices() {
+        let container = serde_json::json!({
+            "HostConfig": {
+                "Devices": [
+                    { "PathOnHost": "/dev/fuse", "PathInContainer": "/dev/fuse" },
+                    { "PathOnHost": "nvidia.com/gpu=all", "PathInContainer": "" }
+                ]
+            }
+        });
+
+        assert_eq!(
+            PodmanProcessor::extract_devices(&container).unwrap(),
+            vec!["/dev/fuse:/dev/fuse", "nvidia.com/gpu=all"]
+        );
+    }
+
+    #[test]
+    fn test_extract_tmpfs() {
+        let container = serde_json::json!({
+            "HostConfig": {
+                "Tmpfs": {
+                    "/tmp": "rw,rprivate,nosuid,nodev,tmpcopyup",
+                    "/run": "rw,rprivate,size=65536k,mode=755,nosuid,nodev,tmpcopyup",
+                    "/cache": "rw,rprivate,nosuid,nodev,tmpcopyup"
+                }
+            },
+            "Mounts": [
+                { "Type": "tmpfs", "Destination": "/cache", "RW": false },
+                { "Type": "tmpfs", "Destination": "/scratch", "RW": true, "Options": ["nosuid"] },
+                {
+                    "Type": "tmpfs",
+                    "Destination": "/buffers",
+                    "RW": true,
+                    "Options": ["size=1048576"]
+                }
+            ]
+        });
+
+        assert_eq!(
+            PodmanProcessor::extract_tmpfs(&container).unwrap(),
+            vec!["/run:size=65536k,mode=755", "/scratch", "/tmp"]
+        );
+        // Only the mounts the short list cannot express stay in volumes
+        let volumes = PodmanProcessor::extract_volumes(&container).unwrap();
+        let targets: Vec<_> = volumes
+            .iter()
+            .map(|volume| match volume {
+                ServiceVolume::Long(mount) => mount.target.as_str(),
+                ServiceVolume::Short(spec) => spec.as_str(),
+            })
+            .collect();
+        assert_eq!(targets, vec!["/cache", "/buffers"]);
+    }
+
+    #[test]
+    fn test_extract_expose() {
+        let container = serde_json::json!({
+            "Config": { "ExposedPorts": { "80/tcp": {}, "9113/tcp": {} } },
+            "NetworkSettings": {
+                "Ports": {
+                    "80/tcp": [{ "HostIp": "", "HostPort": "8080" }],
+                    "53/udp": null
+                }
+            }
+        });
+
+        assert_eq!(
+            PodmanProcessor::extract_expose(&container).unwrap(),
+            vec!["53/udp", "9113"]
+        );
+    }
+
+    #[test]
+    fn test_volume_definition_from_inspect() {
+        let volume = serde_json::json!({
+            "Name": "media",
+            "Driver": "local",
+            "Labels": { "io.podman.compose.project": "app", "tier": "storage" },
+            "Options": { "type": "cifs", "device": "//nas/media", "o": "username=svc" }
+        });
+
+        let definition = PodmanProcessor::volume_definition(&volume);
+        assert_eq!(definition.name.as_deref(), Some("media"));
+        assert_eq!(definition.driver.as_deref(), Some("local"));
+        assert_eq!(definition.driver_opts.unwrap().len(), 3);
+        assert_eq!(definition.labels.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_extract_networks_endpoint_settings() {
+        let container = serde_json::json!({
+            "Id": "0123456789abcdef",
+            "NetworkSettings": {
+                "Networks": {
+                    "backend": {
+                        "Aliases": ["0123456789ab", "db", "database", "postgres"],
+                        "IPAddress": "10.89.0.4",
+                        "IPAMConfig": { "IPv4Address": "10.89.0.4" },
+                        "MacAddress": "a2:13:5f:00:00:04",
+                        "GwPriority": 5
+                    },
+                    "frontend": {
+                        "IPAddress": "10.89.1.9",
+                        "MacAddress": "a2:13:5f:00:01:09"
+                    }
+                }
+            }
+        });
+
+        let (networks, _) = PodmanProcessor::extract_networks(&container, &["db", "database"]);
+        let Some(ServiceNetworks::Advanced(configs)) = networks else {
+            panic!("expected per-network settings");
+        };
+        let backend = &configs["backend"];
+        assert_eq!(backend.aliases, Some(vec!["postgres".to_string()]));
+        assert_eq!(backend.ipv4_address.as_deref(), Some("10.89.0.4"));
+        assert!(backend.mac_address.is_none());
+        assert!(backend.link_local_ips.is_none());
+        assert_eq!(backend.gw_priority, Some(5));
+        // Nothing was requested on this network
+        assert!(configs["frontend"].is_empty());
+    }
+
+    #[test]
+    fn test_image_defaults_from_inspect() {
+        let image = serde_json::json!({
+            "Config": {
+                "Env": ["PATH=/usr/bin:/bin", "NGINX_VERSION=1.25.3"],
+                "Cmd": ["nginx", "-g", "daemon off;"],
+                "Entrypoint": ["/docker-entrypoint.sh"],
+                "WorkingDir": "",
+                "ExposedPorts": { "80/tcp": {} },
+                "Labels": { "maintainer": "NGINX" }
+            },
+            "Healthcheck": { "Test": ["CMD", "curl", "-f", "http://localhost/"] },
+            "Labels": { "maintainer": "NGINX" }
+        });
+
+        let defaults = PodmanProcessor::image_defaults(&image);
+        assert_eq!(defaults.env["NGINX_VERSION"], "1.25.3");
+        assert_eq!(defaults.cmd.unwrap().len(), 3);
+        assert_eq!(defaults.entrypoint.unwrap(), vec!["/docker-entrypoint.sh"]);
+        assert!(defaults.working_dir.is_none());
+        assert_eq!(defaults.healthcheck.unwrap().test.len(), 4);
+        assert_eq!(defaults.labels["maintainer"], "NGINX");
+        assert_eq!(defaults.exposed_ports, vec!["80"]);
+    }
+
+    #[test]
+    fn test_extract_deploy_config() {
+        // --cpus=0.5 --memory=1g --memory-reservation=512m
+        let cpus = serde_json::json!({
+            "HostConfig": {
+                "NanoCpus": 0,
+                "CpuQuota": 50000,
+                "CpuPeriod": 100000,
+                "Memory": 1073741824,
+                "MemoryReservation": 536870912
+            }
+        });
+        let resources = PodmanProcessor::extract_deploy_config(&cpus)
+            .unwrap()
+            .resources
+            .unwrap();
+        let limits = resources.limits.unwrap();
+        assert_eq!(limits.cpus.as_deref(), Some("0.50"));
+        assert_eq!(limits.memory.as_deref(), Some("1G"));
+        assert_eq!(
+            resources.reservations.unwrap().memory.as_deref(),
+            Some("512M")
+        );
+
+        // A quota over a custom period cannot be expressed as cpus
+        let quota = serde_json::json!({
+            "HostConfig": { "CpuQuota": 25000, "CpuPeriod": 50000, "Memory": 0 }
+        });
+        assert!(PodmanProcessor::extract_deploy_config(&quota).is_none());
+    }
+
+    #[test]
+    fn test_parse_daemon_defaults() {
+        let info = serde_json::json!({
+            "host": {
+                "cgroupManager": "systemd",
+                "logDriver": "k8s-file",
+                "ociRuntime": { "name": "crun" },
+                "rootlessNetworkCmd": "pasta",
+                "security": { "rootless": true }
+            }
+        });
+
+        let defaults = PodmanProcessor::parse_daemon_defaults(&info);
+        assert_eq!(defaults.log_driver, "k8s-file");
+        assert_eq!(defaults.default_runtime.as_deref(), Some("crun"));
+        assert!(defaults.network_modes.contains(&"pasta".to_string()));
+        assert!(!defaults.network_modes.contains(&"slirp4netns".to_string()));
+        assert_eq!(defaults.cgroup_parent.as_deref(), Some("user.slice"));
+    }
+
+    #[test]
+    fn test_share_pod_namespace() {
+        let service = |yaml: &str| -> Service { serde_yaml::from_str(yaml).unwrap() };
+        let mut services = HashMap::new();
+        for (name, image) in [("web", "nginx:1.25"), ("app", "acme/app:3")] {
+            let yaml = format!("image: {image}\nnetwork_mode: container:abc");
+            services.insert(name.to_string(), service(&yaml));
+        }
+        let pod = PodContainers {
+            infra: Some(service("image: podman-pause\nports: [\"8080:80\"]\nnetworks: [shop]")),
+            members: vec![
+                ("2025-03-01T10:00:05Z".to_string(), "app".to_string()),
+                ("2025-03-01T10:00:01Z".to_string(), "web".to_string()),
+            ],
+        };
+        let extension = PodmanExtension {
+            pod: Some("shop".to_string()),
+            pod_labels: Some([("tier".to_string(), "frontend".to_string())].into()),
+        };
+
+        PodmanProcessor::share_pod_namespace(pod, extension, &mut services);
+
+        let web = &services["web"];
+        assert_eq!(web.ports.as_deref(), Some(&["8080:80".to_string()][..]));
+        assert!(web.network_mode.is_none());
+        assert!(matches!(&web.networks, Some(ServiceNetworks::Simple(n)) if n == &["shop"]));
+        let app = &services["app"];
+        assert_eq!(app.network_mode.as_deref(), Some("service:web"));
+        assert_eq!(app.x_podman.as_ref().unwrap().pod.as_deref(), Some("shop"));
+    }
+}
@@ -0,0 +1,392 @@
+/*!
+Copyright (c) 2025 Olympus Chain SAS
+
+This software is licensed under the Olympus Chain Internal Source License (OCISL).
+You may read and modify this code for personal or internal non-commercial use only.
+Commercial use, redistribution, or reuse in other software is prohibited without prior written permission.
+
+Contact: contact@olympus-chain.fr
+*/
+
+use crate::{
+    compose_project, dependencies::retain_known_dependencies, replicas::collapse_replicas,
+    sanitize_service_name, DependencyCondition, DependencyConfig, DependsOn, FileReference,
+    Service, ServiceNetworks, ServiceVolume,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const SERVICE_LABELS: [&str; 2] = ["com.docker.compose.service", "io.podman.compose.service"];
+
+/// What `docker compose` or `podman-compose` recorded about a container it
+/// created.
+#[derive(Debug, Clone, Default)]
+pub struct ComposeLabels {
+    pub project: String,
+    pub service: String,
+    pub container_number: Option<u32>,
+    pub depends_on: Option<DependsOn>,
+    pub working_dir: Option<String>,
+}
+
+impl ComposeLabels {
+    pub fn from_labels(labels: &HashMap<String, String>) -> Option<Self> {
+        let project = compose_project(labels)?;
+        let service = SERVICE_LABELS
+            .iter()
+            .find_map(|key| labels.get(*key))
+            .filter(|service| !service.is_empty())?
+            .clone();
+
+        Some(Self {
+            project,
+            service,
+            container_number: labels
+                .get("com.docker.compose.container-number")
+                .and_then(|n| n.parse().ok()),
+            depends_on: labels
+                .get("com.docker.compose.depends_on")
+                .and_then(|value| parse_depends_on_label(value)),
+            working_dir: labels
+                .get("com.docker.compose.project.working_dir")
+                .filter(|dir| !dir.is_empty())
+                .cloned(),
+        })
+    }
+}
+
+/// Parses `com.docker.compose.depends_on`, a comma separated list of
+/// `service:condition:restart` entries.
+pub fn parse_depends_on_label(value: &str) -> Option<DependsOn> {
+    let mut dependencies = HashMap::new();
+
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let mut parts = entry.split(':');
+        let Some(service) = parts.next().filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        let condition = match parts.next() {
+            Some("service_healthy") => DependencyCondition::ServiceHealthy,
+            Some("service_completed_successfully") => {
+                DependencyCondition::ServiceCompletedSuccessfully
+            }
+            _ => DependencyCondition::ServiceStarted,
+        };
+        let restart = parts
+            .next()
+            .and_then(|r| r.parse::<bool>().ok())
+            .filter(|r| *r);
+
+        dependencies.insert(
+            service.to_string(),
+            DependencyConfig {
+                condition,
+                restart,
+                required: None,
+            },
+        );
+    }
+
+    if dependencies.is_empty() {
+        return None;
+    }
+
+    let short_form = dependencies
+        .values()
+        .all(|d| d.condition == DependencyCondition::ServiceStarted && d.restart.is_none());
+    Some(if short_form {
+        let mut services: Vec<String> = dependencies.into_keys().collect();
+        services.sort();
+        DependsOn::Simple(services)
+    } else {
+        DependsOn::Extended(dependencies)
+    })
+}
+
+/// The services that belong in one compose file.
+#[derive(Debug)]
+pub struct ComposeProject {
+    /// `None` gathers the containers no compose project created.
+    pub name: Option<String>,
+    pub working_dir: Option<String>,
+    pub services: HashMap<String, Service>,
+}
+
+impl ComposeProject {
+    /// Names of the networks and named volumes the services refer to.
+    pub fn resources(&self) -> (HashSet<String>, HashSet<String>) {
+        let mut networks = HashSet::new();
+        let mut volumes = HashSet::new();
+
+        for service in self.services.values() {
+            match &service.networks {
+                Some(ServiceNetworks::Simple(names)) => networks.extend(names.iter().cloned()),
+                Some(ServiceNetworks::Advanced(configs)) => {
+                    networks.extend(configs.keys().cloned())
+                }
+                None => {}
+            }
+
+            for volume in service.volumes.iter().flatten() {
+                let source = match volume {
+                    ServiceVolume::Short(spec) => spec.split(':').next(),
+                    ServiceVolume::Long(mount) if mount.mount_type == "volume" => {
+                        mount.source.as_deref()
+                    }
+                    ServiceVolume::Long(_) => None,
+                };
+                volumes.extend(source.map(|s| s.to_string()));
+            }
+        }
+
+        (networks, volumes)
+    }
+
+    /// Names of the secrets and configs the services refer to.
+    pub fn secrets_and_configs(&self) -> (HashSet<String>, HashSet<String>) {
+        let sources = |refs: Option<&Vec<FileReference>>| -> Vec<String> {
+            refs.into_iter().flatten().map(|r| r.source.clone()).collect()
+        };
+
+        let mut secrets = HashSet::new();
+        let mut configs = HashSet::new();
+        for service in self.services.values() {
+            secrets.extend(sources(service.secrets.as_ref()));
+            configs.extend(sources(service.configs.as_ref()));
+        }
+        (secrets, configs)
+    }
+}
+
+/// Splits services into their original compose projects, restoring the
+/// service names and `depends_on` recorded by compose, and folding replicas
+/// into `deploy.replicas`. Projects are sorted by name, with the containers
+/// outside any project last.
+pub fn group_by_project(services: HashMap<String, Service>) -> Vec<ComposeProject> {
+    let mut groups: BTreeMap<Option<String>, HashMap<String, Service>> = BTreeMap::new();
+    for (name, service) in services {
+        let project = service.compose.as_ref().map(|c| c.project.clone());
+        groups.entry(project).or_default().insert(name, service);
+    }
+
+    let mut projects: Vec<ComposeProject> = groups
+        .into_iter()
+        .map(|(name, services)| restore_project(name, services))
+        .collect();
+
+    projects.sort_by_key(|p| p.name.is_none());
+    projects
+}
+
+fn restore_project(
+    project: Option<String>,
+    mut services: HashMap<String, Service>,
+) -> ComposeProject {
+    let mut renames = collapse_replicas(&mut services);
+    for (name, service) in &services {
+        if let Some(compose) = &service.compose {
+            renames.insert(name.clone(), compose.service.clone());
+        }
+    }
+
+    let mut working_dir = None;
+    let mut restored = HashMap::new();
+    for (name, mut service) in services {
+        let name = renames.get(&name).cloned().unwrap_or(name);
+
+        match &service.compose {
+            Some(compose) => {
+                working_dir = working_dir.or_else(|| compose.working_dir.clone());
+
+                if service
+                    .container_name
+                    .as_deref()
+                    .is_some_and(|container_name| {
+                        is_generated_container_name(container_name, compose)
+                    })
+                {
+                    service.container_name = None;
+                }
+
+                match &compose.depends_on {
+                    Some(depends_on) => service.depends_on = Some(depends_on.clone()),
+                    None => rename_dependencies(&mut service.depends_on, &name, &renames),
+                }
+            }
+            None => rename_dependencies(&mut service.depends_on, &name, &renames),
+        }
+        for mode in [
+            &mut service.network_mode,
+            &mut service.pid,
+            &mut service.ipc,
+        ] {
+            let target = mode.as_deref().and_then(|m| m.strip_prefix("service:"));
+            if let Some(renamed) = target.and_then(|target| renames.get(target)) {
+                *mode = Some(format!("service:{}", renamed));
+            }
+        }
+
+        restored.insert(name, service);
+    }
+
+    retain_known_dependencies(&mut restored);
+    ComposeProject {
+        name: project,
+        working_dir,
+        services: restored,
+    }
+}
+
+/// Whether compose picked the container name itself, as
+/// `<project>-<service>-<n>` (or with underscores for older releases).
+fn is_generated_container_name(name: &str, compose: &ComposeLabels) -> bool {
+    let project = &compose.project;
+    let number = compose.container_number.unwrap_or(1);
+    ['-', '_'].iter().any(|sep| {
+        let generated = format!("{project}{sep}{}{sep}{number}", compose.service);
+        name == generated || name == sanitize_service_name(&generated)
+    })
+}
+
+/// Points `depends_on` at the restored service names. Replicas of the
+/// service itself are no longer a dependency once folded into it.
+fn rename_dependencies(
+    depends_on: &mut Option<DependsOn>,
+    service: &str,
+    renames: &HashMap<String, String>,
+) {
+    let rename = |name: String| renames.get(&name).cloned().unwrap_or(name);
+    *depends_on = depends_on.take().and_then(|depends_on| {
+        let mut depends_on = match depends_on {
+            DependsOn::Simple(services) => {
+                let mut services: Vec<String> = services.into_iter().map(rename).collect();
+                services.sort();
+                services.dedup();
+                DependsOn::Simple(services)
+            }
+            DependsOn::Extended(services) => DependsOn::Extended(
+                services
+                    .into_iter()
+                    .map(|(name, config)| (rename(name), config))
+                    .collect(),
+            ),
+        };
+        depends_on.retain(|dependency| dependency != service);
+        (!depends_on.is_empty()).then_some(depends_on)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compose_service(project: &str, service: &str, extra: &[(&str, &str)]) -> Service {
+        let mut labels: HashMap<String, String> = [
+            ("com.docker.compose.project", project),
+            ("com.docker.compose.service", service),
+            ("com.docker.compose.container-number", "1"),
+            ("com.docker.compose.project.working_dir", "/srv/shop"),
+        ]
+        .into_iter()
+        .chain(extra.iter().copied())
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        labels.retain(|_, v| !v.is_empty());
+
+        let mut service: Service = serde_yaml::from_str(&format!(
+            "image: {service}:latest\ncontainer_name: {project}-{service}-1"
+        ))
+        .unwrap();
+        service.compose = ComposeLabels::from_labels(&labels);
+        service
+    }
+
+    #[test]
+    fn test_parse_depends_on_label() {
+        let simple = parse_depends_on_label("db:service_started:false,cache:service_started:false");
+        assert_eq!(simple.unwrap().services(), vec!["cache", "db"]);
+
+        let Some(DependsOn::Extended(extended)) = parse_depends_on_label(
+            "db:service_healthy:true,migrate:service_completed_successfully",
+        ) else {
+            panic!("expected the long form");
+        };
+        assert_eq!(
+            extended["db"].condition,
+            DependencyCondition::ServiceHealthy
+        );
+        assert_eq!(extended["db"].restart, Some(true));
+        assert_eq!(
+            extended["migrate"].condition,
+            DependencyCondition::ServiceCompletedSuccessfully
+        );
+
+        assert!(parse_depends_on_label("").is_none());
+    }
+
+    #[test]
+    fn test_group_by_project() {
+        let mut services = HashMap::new();
+        services.insert(
+            "shop-web-1".to_string(),
+            compose_service(
+                "shop",
+                "web",
+                &[("com.docker.compose.depends_on", "db:service_healthy:false")],
+            ),
+        );
+        services.insert("shop-db-1".to_string(), compose_service("shop", "db", &[]));
+        services.insert(
+            "blog-web-1".to_string(),
+            compose_service("blog", "web", &[]),
+        );
+        services.insert(
+            "standalone".to_string(),
+            serde_yaml::from_str("image: redis:7\ndepends_on: [shop-db-1]").unwrap(),
+        );
+
+        let projects = group_by_project(services);
+        let names: Vec<Option<&str>> = projects.iter().map(|p| p.name.as_deref()).collect();
+        assert_eq!(names, vec![Some("blog"), Some("shop"), None]);
+
+        let shop = &projects[1];
+        assert_eq!(shop.working_dir.as_deref(), Some("/srv/shop"));
+        let mut service_names: Vec<&str> = shop.services.keys().map(|s| s.as_str()).collect();
+        service_names.sort_unstable();
+        assert_eq!(service_names, vec!["db", "web"]);
+        assert!(shop.services["web"].container_name.is_none());
+        assert_eq!(
+            shop.services["web"].depends_on.as_ref().unwrap().services(),
+            vec!["db"]
+        );
+
+        // Dependencies cannot cross into another compose file
+        assert!(projects[2].services["standalone"].depends_on.is_none());
+    }
+
+    #[test]
+    fn test_fold_compose_replicas() {
+        let mut services = HashMap::new();
+        for number in ["1", "2", "3"] {
+            services.insert(
+                format!("shop-worker-{number}"),
+                compose_service(
+                    "shop",
+                    "worker",
+                    &[("com.docker.compose.container-number", number)],
+                ),
+            );
+        }
+        let mut web = compose_service("shop", "web", &[]);
+        web.depends_on = Some(DependsOn::Simple(vec!["shop-worker-2".to_string()]));
+        services.insert("shop-web-1".to_string(), web);
+
+        let projects = group_by_project(services);
+        let shop = &projects[0].services;
+        assert_eq!(shop.len(), 2);
+        assert_eq!(shop["worker"].deploy.as_ref().unwrap().replicas, Some(3));
+        assert_eq!(
+            shop["web"].depends_on.as_ref().unwrap().services(),
+            vec!["worker"]
+        );
+    }
+}
@@ -0,0 +1,404 @@
+/*!
+Copyright (c) 2025 Olympus Chain SAS
+
+This software is licensed under the Olympus Chain Internal Source License (OCISL).
+You may read and modify this code for personal or internal non-commercial use only.
+Commercial use, redistribution, or reuse in other software is prohibited without prior written permission.
+
+Contact: contact@olympus-chain.fr
+*/
+
+use crate::{parse_size_bytes, HealthCheck, Service};
+use std::collections::HashMap;
+
+/// The settings an image bakes in. A container inherits all of them, so
+/// repeating them in the compose file only adds noise.
+#[derive(Debug, Clone, Default)]
+pub struct ImageDefaults {
+    pub env: HashMap<String, String>,
+    pub cmd: Option<Vec<String>>,
+    pub entrypoint: Option<Vec<String>>,
+    pub working_dir: Option<String>,
+    pub user: Option<String>,
+    pub healthcheck: Option<HealthCheck>,
+    pub labels: HashMap<String, String>,
+    pub stop_signal: Option<String>,
+    /// Exposed ports, in the form of `expose` entries.
+    pub exposed_ports: Vec<String>,
+}
+
+impl ImageDefaults {
+    /// Parses `KEY=value` entries as found in `Config.Env`.
+    pub fn parse_env<'a>(entries: impl IntoIterator<Item = &'a str>) -> HashMap<String, String> {
+        entries
+            .into_iter()
+            .filter_map(|entry| entry.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+}
+
+/// Removes every value of `service` that is identical to what the image
+/// already provides.
+pub fn prune_image_defaults(service: &mut Service, defaults: &ImageDefaults) {
+    if let Some(env) = service.environment.as_mut() {
+        env.retain(|key, value| defaults.env.get(key) != Some(value));
+        if env.is_empty() {
+            service.environment = None;
+        }
+    }
+
+    if let Some(labels) = service.labels.as_mut() {
+        labels.retain(|key, value| defaults.labels.get(key) != Some(value));
+        if labels.is_empty() {
+            service.labels = None;
+        }
+    }
+
+    // Overriding the entrypoint resets the image command, so the command is
+    // only inherited when the entrypoint is left as it is.
+    let same_entrypoint = service.entrypoint.is_none()
+        || normalized(&service.entrypoint) == normalized(&defaults.entrypoint);
+    if same_entrypoint {
+        service.entrypoint = None;
+        if normalized(&service.command) == normalized(&defaults.cmd) {
+            service.command = None;
+        }
+    }
+
+    if service.working_dir.is_some() && service.working_dir == defaults.working_dir {
+        service.working_dir = None;
+    }
+
+    if service.user.is_some() && service.user == defaults.user {
+        service.user = None;
+    }
+
+    if service.healthcheck.is_some() && service.healthcheck == defaults.healthcheck {
+        service.healthcheck = None;
+    }
+
+    if let Some(expose) = service.expose.as_mut() {
+        expose.retain(|port| !defaults.exposed_ports.contains(port));
+        if expose.is_empty() {
+            service.expose = None;
+        }
+    }
+
+    // Without a STOPSIGNAL in the image, containers are stopped with SIGTERM
+    let stop_signal = defaults.stop_signal.as_deref().unwrap_or("SIGTERM");
+    if service.stop_signal.as_deref() == Some(stop_signal) {
+        service.stop_signal = None;
+    }
+}
+
+/// The runtime defaults of the engine the containers were read from, as
+/// reported by `docker info` / `podman info`.
+#[derive(Debug, Clone)]
+pub struct DaemonDefaults {
+    pub log_driver: String,
+    pub default_runtime: Option<String>,
+    /// Network modes that stand for the engine's default network.
+    pub network_modes: Vec<String>,
+    /// IPC mode given to containers that do not ask for one.
+    pub ipc_mode: String,
+    /// Cgroup namespace mode, which follows the host's cgroup version.
+    pub cgroupns_mode: Option<String>,
+    /// Parent cgroup the engine places containers under by default.
+    pub cgroup_parent: Option<String>,
+    /// Size of `/dev/shm`, in bytes.
+    pub shm_size: i64,
+    /// Process limit applied by the engine's configuration, if any.
+    pub pids_limit: Option<i64>,
+}
+
+impl DaemonDefaults {
+    /// Stock Docker defaults, used when the daemon cannot be queried.
+    pub fn docker() -> Self {
+        Self {
+            log_driver: "json-file".to_string(),
+            default_runtime: Some("runc".to_string()),
+            network_modes: vec!["default".to_string(), "bridge".to_string()],
+            ipc_mode: "private".to_string(),
+            cgroupns_mode: Some("private".to_string()),
+            cgroup_parent: None,
+            shm_size: 64 * 1024 * 1024,
+            pids_limit: None,
+        }
+    }
+
+    /// Stock Podman defaults, used when `podman info` is unavailable.
+    pub fn podman() -> Self {
+        Self {
+            log_driver: "journald".to_string(),
+            default_runtime: None,
+            network_modes: vec!["default".to_string(), "bridge".to_string()],
+            ipc_mode: "shareable".to_string(),
+            cgroupns_mode: Some("private".to_string()),
+            cgroup_parent: None,
+            shm_size: 65536 * 1000,
+            pids_limit: Some(2048),
+        }
+    }
+}
+
+/// Removes every value of `service` that only restates a daemon default.
+pub fn prune_daemon_defaults(service: &mut Service, defaults: &DaemonDefaults) {
+    if service.restart.as_deref() == Some("no") {
+        service.restart = None;
+    }
+
+    let default_logging = service.logging.as_ref().is_some_and(|logging| {
+        (logging.driver.is_empty() || logging.driver == defaults.log_driver)
+            && logging.options.as_ref().is_none_or(|o| o.is_empty())
+    });
+    if default_logging {
+        service.logging = None;
+    }
+
+    if service
+        .network_mode
+        .as_ref()
+        .is_some_and(|mode| defaults.network_modes.contains(mode))
+    {
+        service.network_mode = None;
+    }
+
+    if service.stop_grace_period.as_deref() == Some("10s") {
+        service.stop_grace_period = None;
+    }
+
+    for flag in [
+        &mut service.tty,
+        &mut service.stdin_open,
+        &mut service.init,
+        &mut service.privileged,
+        &mut service.read_only,
+    ] {
+        if *flag == Some(false) {
+            *flag = None;
+        }
+    }
+
+    if service.ulimits.as_ref().is_some_and(|u| u.is_empty()) {
+        service.ulimits = None;
+    }
+
+    // An unshared namespace is what every container gets
+    for mode in [&mut service.pid, &mut service.uts, &mut service.userns_mode] {
+        if matches!(mode.as_deref(), Some("" | "private")) {
+            *mode = None;
+        }
+    }
+    let defaults_to = |value: &Option<String>, default: Option<&str>| {
+        value.as_deref().is_some_and(|v| v.is_empty() || Some(v) == default)
+    };
+    if defaults_to(&service.ipc, Some(&defaults.ipc_mode)) {
+        service.ipc = None;
+    }
+    if defaults_to(&service.cgroup, defaults.cgroupns_mode.as_deref()) {
+        service.cgroup = None;
+    }
+    if defaults_to(&service.cgroup_parent, defaults.cgroup_parent.as_deref()) {
+        service.cgroup_parent = None;
+    }
+    if defaults_to(&service.runtime, defaults.default_runtime.as_deref()) {
+        service.runtime = None;
+    }
+
+    // 1024 is the relative weight every container starts with
+    if service.cpu_shares == Some(1024) {
+        service.cpu_shares = None;
+    }
+    if service.pids_limit.is_some() && service.pids_limit == defaults.pids_limit {
+        service.pids_limit = None;
+    }
+    if service.shm_size.as_deref().and_then(parse_size_bytes) == Some(defaults.shm_size) {
+        service.shm_size = None;
+    }
+}
+
+fn normalized(args: &Option<Vec<String>>) -> Option<&[String]> {
+    args.as_deref().filter(|a| !a.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nginx_defaults() -> ImageDefaults {
+        ImageDefaults {
+            env: ImageDefaults::parse_env([
+                "PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin",
+                "NGINX_VERSION=1.25.3",
+            ]),
+            cmd: Some(vec!["nginx".into(), "-g".into(), "daemon off;".into()]),
+            entrypoint: Some(vec!["/docker-entrypoint.sh".into()]),
+            working_dir: None,
+            user: None,
+            healthcheck: None,
+            labels: [("maintainer".to_string(), "NGINX".to_string())].into(),
+            stop_signal: Some("SIGQUIT".to_string()),
+            exposed_ports: vec!["80".to_string()],
+        }
+    }
+
+    fn service(yaml: &str) -> Service {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_prune_inherited_values() {
+        let mut web = service(
+            r#"
+image: nginx:1.25
+environment:
+  PATH: /usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin
+  NGINX_VERSION: 1.25.3
+  BACKEND_URL: http://api:8080
+labels:
+  maintainer: NGINX
+  tier: frontend
+entrypoint: [/docker-entrypoint.sh]
+command: [nginx, -g, daemon off;]
+stop_signal: SIGQUIT
+expose: ["80", "9113"]
+"#,
+        );
+
+        prune_image_defaults(&mut web, &nginx_defaults());
+
+        let env = web.environment.unwrap();
+        assert_eq!(env.len(), 1);
+        assert_eq!(env["BACKEND_URL"], "http://api:8080");
+        assert_eq!(web.labels.unwrap().len(), 1);
+        assert!(web.entrypoint.is_none());
+        assert!(web.command.is_none());
+        assert!(web.stop_signal.is_none());
+        assert_eq!(web.expose.unwrap(), vec!["9113"]);
+    }
+
+    #[test]
+    fn test_keep_command_when_entrypoint_overridden() {
+        let mut debug = service(
+            r#"
+image: nginx:1.25
+environment:
+  NGINX_VERSION: 1.25.3
+entrypoint: [/bin/sh, -c]
+command: [nginx, -g, daemon off;]
+stop_signal: SIGTERM
+"#,
+        );
+
+        prune_image_defaults(&mut debug, &nginx_defaults());
+
+        assert!(debug.environment.is_none());
+        assert_eq!(debug.entrypoint.unwrap(), vec!["/bin/sh", "-c"]);
+        assert_eq!(debug.command.unwrap().len(), 3);
+        assert_eq!(debug.stop_signal.as_deref(), Some("SIGTERM"));
+    }
+
+    #[test]
+    fn test_prune_daemon_defaults() {
+        let mut web = service(
+            r#"
+image: nginx:1.25
+restart: "no"
+network_mode: bridge
+logging:
+  driver: json-file
+tty: false
+stdin_open: true
+init: false
+privileged: false
+read_only: false
+stop_grace_period: 10s
+ulimits: {}
+"#,
+        );
+
+        prune_daemon_defaults(&mut web, &DaemonDefaults::docker());
+
+        assert!(web.restart.is_none());
+        assert!(web.network_mode.is_none());
+        assert!(web.logging.is_none());
+        assert!(web.tty.is_none());
+        assert_eq!(web.stdin_open, Some(true));
+        assert!(web.init.is_none() && web.privileged.is_none());
+        assert!(web.read_only.is_none() && web.stop_grace_period.is_none());
+        assert!(web.ulimits.is_none());
+    }
+
+    #[test]
+    fn test_prune_default_namespaces() {
+        let mut sandboxed = service(
+            r#"
+image: nginx:1.25
+pid: private
+ipc: private
+uts: host
+cgroup: private
+runtime: runsc
+"#,
+        );
+
+        prune_daemon_defaults(&mut sandboxed, &DaemonDefaults::docker());
+
+        assert!(sandboxed.pid.is_none() && sandboxed.ipc.is_none());
+        assert!(sandboxed.cgroup.is_none());
+        assert_eq!(sandboxed.uts.as_deref(), Some("host"));
+        assert_eq!(sandboxed.runtime.as_deref(), Some("runsc"));
+    }
+
+    #[test]
+    fn test_prune_default_resources() {
+        let mut db = service(
+            r#"
+image: postgres:16
+cpu_shares: 1024
+pids_limit: 2048
+shm_size: 64000K
+"#,
+        );
+        let mut cache = service(
+            r#"
+image: redis:7
+cpu_shares: 512
+pids_limit: 2048
+shm_size: 64M
+"#,
+        );
+
+        prune_daemon_defaults(&mut db, &DaemonDefaults::podman());
+        prune_daemon_defaults(&mut cache, &DaemonDefaults::docker());
+
+        assert!(db.cpu_shares.is_none() && db.pids_limit.is_none() && db.shm_size.is_none());
+        assert_eq!(cache.cpu_shares, Some(512));
+        assert_eq!(cache.pids_limit, Some(2048));
+        assert!(cache.shm_size.is_none());
+    }
+
+    #[test]
+    fn test_keep_non_default_logging() {
+        let mut web = service(
+            r#"
+image: nginx:1.25
+restart: unless-stopped
+logging:
+  driver: json-file
+  options:
+    max-size: 10m
+"#,
+        );
+        let journald = DaemonDefaults {
+            log_driver: "journald".to_string(),
+            ..DaemonDefaults::podman()
+        };
+
+        prune_daemon_defaults(&mut web, &journald);
+
+        assert_eq!(web.restart.as_deref(), Some("unless-stopped"));
+        assert_eq!(web.logging.unwrap().options.unwrap()["max-size"], "10m");
+    }
+}
@@ -0,0 +1,859 @@
+/*!
+Copyright (c) 2025 Olympus Chain SAS
+
+This software is licensed under the Olympus Chain Internal Source License (OCISL).
+You may read and modify this code for personal or internal non-commercial use only.
+Commercial use, redistribution, or reuse in other software is prohibited without prior written permission.
+
+Contact: contact@olympus-chain.fr
+*/
+
+use crate::{
+    kubernetes::duration_seconds,
+    run_script::{cdi_gpus, gpus, is_gpu, resource_flags},
+    ComposeFile, DependencyCondition, DependsOn, NetworkConfig, NetworkDefinition, Service,
+    ServiceNetworks, ServiceVolume, VolumeDefinition, VolumeMount,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// One unit file, named after the resource it defines.
+#[derive(Debug)]
+pub struct QuadletFile {
+    pub name: String,
+    pub contents: String,
+}
+
+#[derive(Debug, Default)]
+pub struct QuadletExport {
+    pub files: Vec<QuadletFile>,
+    /// Settings Quadlet has no equivalent for, left out of the units.
+    pub warnings: Vec<String>,
+}
+
+#[derive(Default)]
+struct UnitFile {
+    sections: Vec<(&'static str, Vec<(&'static str, String)>)>,
+}
+
+impl UnitFile {
+    fn add(&mut self, section: &'static str, key: &'static str, value: impl Into<String>) {
+        let index = match self.sections.iter().position(|(name, _)| *name == section) {
+            Some(index) => index,
+            None => {
+                self.sections.push((section, Vec::new()));
+                self.sections.len() - 1
+            }
+        };
+        self.sections[index].1.push((key, value.into()));
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        for (index, (section, entries)) in self.sections.iter().enumerate() {
+            if index > 0 {
+                out.push('\n');
+            }
+            out.push_str(&format!("[{}]\n", section));
+            for (key, value) in entries {
+                out.push_str(&format!("{}={}\n", key, value));
+            }
+        }
+        out
+    }
+}
+
+/// Pods collected from the services' `x-podman` metadata.
+#[derive(Default)]
+struct PodUnit {
+    labels: BTreeMap<String, String>,
+    ports: Vec<String>,
+    networks: Vec<String>,
+}
+
+struct Exporter<'a> {
+    network_units: BTreeSet<&'a str>,
+    volume_units: BTreeSet<&'a str>,
+    warnings: Vec<String>,
+}
+
+/// Converts a compose model into Quadlet units. Networks and volumes only get
+/// a unit of their own when the compose file defines them and they are not
+/// external; otherwise they are referred to by name.
+pub fn export_quadlet(compose: &ComposeFile) -> QuadletExport {
+    let networks = compose.network_definitions();
+    let volumes = compose.volume_definitions();
+
+    let mut exporter = Exporter {
+        network_units: networks
+            .iter()
+            .filter(|(name, def)| def.external != Some(true) && !is_predefined_network(name))
+            .map(|(name, _)| name.as_str())
+            .collect(),
+        volume_units: volumes
+            .iter()
+            .filter(|(_, def)| def.external != Some(true))
+            .map(|(name, _)| name.as_str())
+            .collect(),
+        warnings: Vec::new(),
+    };
+
+    let mut files = Vec::new();
+    let mut pods: BTreeMap<String, PodUnit> = BTreeMap::new();
+
+    let mut names: Vec<&String> = compose.services.keys().collect();
+    names.sort();
+    for name in names {
+        let unit = exporter.container_unit(name, &compose.services[name], &mut pods);
+        files.push(QuadletFile {
+            name: format!("{}.container", name),
+            contents: unit.render(),
+        });
+    }
+
+    for (name, pod) in pods {
+        let mut unit = UnitFile::default();
+        unit.add("Pod", "PodName", name.as_str());
+        for (key, value) in pod.labels {
+            unit.add("Pod", "Label", quote(&format!("{}={}", key, value)));
+        }
+        for port in pod.ports {
+            unit.add("Pod", "PublishPort", port);
+        }
+        for network in pod.networks {
+            unit.add("Pod", "Network", network);
+        }
+        files.push(QuadletFile {
+            name: format!("{}.pod", name),
+            contents: unit.render(),
+        });
+    }
+
+    for (name, definition) in &networks {
+        if exporter.network_units.contains(name.as_str()) {
+            let unit = exporter.network_unit(name, definition);
+            files.push(QuadletFile {
+                name: format!("{}.network", name),
+                contents: unit.render(),
+            });
+        }
+    }
+
+    for (name, definition) in &volumes {
+        if exporter.volume_units.contains(name.as_str()) {
+            let unit = exporter.volume_unit(name, definition);
+            files.push(QuadletFile {
+                name: format!("{}.volume", name),
+                contents: unit.render(),
+            });
+        }
+    }
+
+    QuadletExport {
+        files,
+        warnings: exporter.warnings,
+    }
+}
+
+impl Exporter<'_> {
+    fn warn(&mut self, service: &str, message: impl std::fmt::Display) {
+        self.warnings.push(format!("{}: {}", service, message));
+    }
+
+    fn container_unit(
+        &mut self,
+        name: &str,
+        service: &Service,
+        pods: &mut BTreeMap<String, PodUnit>,
+    ) -> UnitFile {
+        let mut unit = UnitFile::default();
+        unit.add("Unit", "Description", format!("{} container", name));
+
+        let pod = service.x_podman.as_ref().and_then(|x| x.pod.clone());
+        let mut dependencies: BTreeSet<String> = BTreeSet::new();
+
+        unit.add("Container", "Image", service.image.as_str());
+        if let Some(container_name) = &service.container_name {
+            unit.add("Container", "ContainerName", container_name.as_str());
+        }
+        if let Some(hostname) = &service.hostname {
+            unit.add("Container", "HostName", hostname.as_str());
+        }
+        if let Some(pod) = &pod {
+            unit.add("Container", "Pod", format!("{}.pod", pod));
+            let pod_unit = pods.entry(pod.clone()).or_default();
+            if let Some(labels) = service
+                .x_podman
+                .as_ref()
+                .and_then(|x| x.pod_labels.as_ref())
+            {
+                pod_unit.labels.extend(labels.clone());
+            }
+        }
+
+        for (key, value) in sorted(service.environment.as_ref()) {
+            unit.add(
+                "Container",
+                "Environment",
+                quote(&format!("{}={}", key, value)),
+            );
+        }
+        for (key, value) in sorted(service.labels.as_ref()) {
+            unit.add("Container", "Label", quote(&format!("{}={}", key, value)));
+        }
+
+        // Containers of a pod share its namespaces; ports and networks go on the pod
+        let ports = service.ports.iter().flatten().cloned();
+        match &pod {
+            Some(pod) => pods.get_mut(pod).unwrap().ports.extend(ports),
+            None => ports.for_each(|port| unit.add("Container", "PublishPort", port)),
+        }
+        for port in service.expose.iter().flatten() {
+            unit.add("Container", "ExposeHostPort", port.as_str());
+        }
+
+        let networks = self.networks(name, service, &mut dependencies);
+        match &pod {
+            Some(pod) => {
+                let pod_unit = pods.get_mut(pod).unwrap();
+                for network in networks {
+                    if !pod_unit.networks.contains(&network) {
+                        pod_unit.networks.push(network);
+                    }
+                }
+            }
+            None => networks
+                .into_iter()
+                .for_each(|network| unit.add("Container", "Network", network)),
+        }
+
+        for volume in service.volumes.iter().flatten() {
+            match volume {
+                ServiceVolume::Short(spec) => {
+                    unit.add("Container", "Volume", self.volume_spec(spec));
+                }
+                ServiceVolume::Long(mount) if mount.mount_type == "tmpfs" => {
+                    unit.add("Container", "Tmpfs", tmpfs_spec(mount));
+                }
+                ServiceVolume::Long(mount) => match self.mount_spec(mount) {
+                    Some(spec) => unit.add("Container", "Volume", spec),
+                    None => self.warn(
+                        name,
+                        format!("{} mount of {}", mount.mount_type, mount.target),
+                    ),
+                },
+            }
+        }
+        for tmpfs in service.tmpfs.iter().flatten() {
+            unit.add("Container", "Tmpfs", tmpfs.as_str());
+        }
+
+        for dns in service.dns.iter().flatten() {
+            unit.add("Container", "DNS", dns.as_str());
+        }
+        for search in service.dns_search.iter().flatten() {
+            unit.add("Container", "DNSSearch", search.as_str());
+        }
+        for host in service.extra_hosts.iter().flatten() {
+            unit.add("Container", "AddHost", host.as_str());
+        }
+        if let Some(caps) = service.cap_add.as_ref().filter(|c| !c.is_empty()) {
+            unit.add("Container", "AddCapability", caps.join(" "));
+        }
+        if let Some(caps) = service.cap_drop.as_ref().filter(|c| !c.is_empty()) {
+            unit.add("Container", "DropCapability", caps.join(" "));
+        }
+        for opt in service.security_opt.iter().flatten() {
+            unit.add(
+                "Container",
+                "PodmanArgs",
+                format!("--security-opt={}", quote(opt)),
+            );
+        }
+        for device in service.devices.iter().flatten() {
+            unit.add("Container", "AddDevice", device.as_str());
+        }
+        for device in gpus(service).flat_map(cdi_gpus) {
+            unit.add("Container", "AddDevice", device);
+        }
+
+        if let Some(user) = &service.user {
+            match user.split_once(':') {
+                Some((user, group)) => {
+                    unit.add("Container", "User", user);
+                    unit.add("Container", "Group", group);
+                }
+                None => unit.add("Container", "User", user.as_str()),
+            }
+        }
+        if let Some(dir) = &service.working_dir {
+            unit.add("Container", "WorkingDir", dir.as_str());
+        }
+        if let Some(entrypoint) = &service.entrypoint {
+            unit.add("Container", "Entrypoint", exec_line(entrypoint));
+        }
+        if let Some(command) = &service.command {
+            unit.add("Container", "Exec", exec_line(command));
+        }
+
+        if let Some(healthcheck) = &service.healthcheck {
+            match healthcheck.test.split_first() {
+                Some((kind, args)) if kind == "CMD" => {
+                    unit.add("Container", "HealthCmd", exec_line(args));
+                }
+                Some((kind, args)) if kind == "CMD-SHELL" => {
+                    unit.add("Container", "HealthCmd", quote(&args.join(" ")));
+                }
+                Some((kind, _)) if kind == "NONE" => unit.add("Container", "HealthCmd", "none"),
+                _ => self.warn(name, "healthcheck without a CMD or CMD-SHELL test"),
+            }
+            if let Some(interval) = &healthcheck.interval {
+                unit.add("Container", "HealthInterval", interval.as_str());
+            }
+            if let Some(timeout) = &healthcheck.timeout {
+                unit.add("Container", "HealthTimeout", timeout.as_str());
+            }
+            if let Some(retries) = healthcheck.retries {
+                unit.add("Container", "HealthRetries", retries.to_string());
+            }
+            if let Some(start_period) = &healthcheck.start_period {
+                unit.add("Container", "HealthStartPeriod", start_period.as_str());
+            }
+        }
+
+        if let Some(logging) = &service.logging {
+            if !logging.driver.is_empty() {
+                unit.add("Container", "LogDriver", logging.driver.as_str());
+            }
+            for (key, value) in sorted(logging.options.as_ref()) {
+                unit.add(
+                    "Container",
+                    "PodmanArgs",
+                    format!("--log-opt={}={}", key, value),
+                );
+            }
+        }
+
+        let mut ulimits: Vec<_> = service.ulimits.iter().flatten().collect();
+        ulimits.sort_by_key(|(name, _)| name.as_str());
+        for (ulimit, limit) in ulimits {
+            unit.add(
+                "Container",
+                "Ulimit",
+                format!("{}={}:{}", ulimit, limit.soft, limit.hard),
+            );
+        }
+        for (key, value) in sorted(service.sysctls.as_ref()) {
+            unit.add("Container", "Sysctl", format!("{}={}", key, value));
+        }
+
+        if service.init == Some(true) {
+            unit.add("Container", "RunInit", "true");
+        }
+        if service.privileged == Some(true) {
+            unit.add("Container", "PodmanArgs", "--privileged");
+        }
+        if service.tty == Some(true) {
+            unit.add("Container", "PodmanArgs", "--tty");
+        }
+        if service.stdin_open == Some(true) {
+            unit.add("Container", "PodmanArgs", "--interactive");
+        }
+        if service.read_only == Some(true) {
+            unit.add("Container", "ReadOnly", "true");
+        }
+        if let Some(signal) = &service.stop_signal {
+            unit.add("Container", "StopSignal", signal);
+        }
+        if let Some(seconds) = service
+            .stop_grace_period
+            .as_deref()
+            .and_then(duration_seconds)
+        {
+            unit.add("Container", "StopTimeout", seconds.to_string());
+        }
+        for group in service.group_add.iter().flatten() {
+            unit.add("Container", "GroupAdd", group);
+        }
+        for (key, value) in sorted(service.annotations.as_ref()) {
+            unit.add("Container", "Annotation", format!("{}={}", key, value));
+        }
+        let mut podman_args: Vec<String> = Vec::new();
+        if let Some(platform) = &service.platform {
+            podman_args.push(format!("--platform={}", platform));
+        }
+        if let Some(domainname) = &service.domainname {
+            podman_args.push(format!("--domainname={}", domainname));
+        }
+        if let Some(mac_address) = &service.mac_address {
+            podman_args.push(format!("--mac-address={}", mac_address));
+        }
+        for (key, value) in sorted(service.storage_opt.as_ref()) {
+            podman_args.push(format!("--storage-opt={}={}", key, value));
+        }
+        for rule in service.device_cgroup_rules.iter().flatten() {
+            podman_args.push(format!("--device-cgroup-rule={}", rule));
+        }
+        for arg in podman_args {
+            unit.add("Container", "PodmanArgs", arg);
+        }
+        if let Some(userns) = &service.userns_mode {
+            unit.add("Container", "UserNS", userns);
+        }
+        for (flag, value) in [
+            ("--pid", &service.pid),
+            ("--ipc", &service.ipc),
+            ("--uts", &service.uts),
+            ("--cgroupns", &service.cgroup),
+            ("--cgroup-parent", &service.cgroup_parent),
+            ("--runtime", &service.runtime),
+        ] {
+            match value.as_deref() {
+                Some(mode) if mode.starts_with("service:") => {
+                    self.warn(name, format!("{} {}", flag.trim_start_matches('-'), mode))
+                }
+                Some(value) => unit.add("Container", "PodmanArgs", format!("{}={}", flag, value)),
+                None => {}
+            }
+        }
+        if let Some(isolation) = &service.isolation {
+            self.warn(name, format!("isolation {}", isolation));
+        }
+
+        for (flag, value) in resource_flags(service) {
+            match value {
+                Some(value) => unit.add("Container", "PodmanArgs", format!("{}={}", flag, value)),
+                None => unit.add("Container", "PodmanArgs", flag),
+            }
+        }
+
+        if let Some(deploy) = &service.deploy {
+            if deploy.replicas.is_some_and(|replicas| replicas > 1) {
+                self.warn(
+                    name,
+                    "deploy.replicas (use a template unit to run several instances)",
+                );
+            }
+            let devices = deploy
+                .resources
+                .as_ref()
+                .and_then(|r| r.reservations.as_ref())
+                .and_then(|r| r.devices.as_ref());
+            if devices.into_iter().flatten().any(|device| !is_gpu(device)) {
+                self.warn(name, "device reservations other than NVIDIA GPUs");
+            }
+            if deploy.placement.is_some() {
+                self.warn(name, "deploy.placement");
+            }
+        }
+
+        if let Some(depends_on) = &service.depends_on {
+            if let DependsOn::Extended(deps) = depends_on {
+                if deps
+                    .values()
+                    .any(|d| d.condition != DependencyCondition::ServiceStarted)
+                {
+                    self.warn(name, "depends_on conditions (only the start order is kept)");
+                }
+            }
+            dependencies.extend(depends_on.services().into_iter().map(|s| s.to_string()));
+        }
+        for dependency in dependencies {
+            unit.add("Unit", "Requires", format!("{}.service", dependency));
+            unit.add("Unit", "After", format!("{}.service", dependency));
+        }
+
+        match service.restart.as_deref() {
+            None | Some("no") => {}
+            Some(restart) => {
+                let (policy, retries) = restart.split_once(':').unwrap_or((restart, ""));
+                let policy = match policy {
+                    "always" | "unless-stopped" => "always",
+                    "on-failure" => "on-failure",
+                    other => {
+                        self.warn(name, format!("restart policy {}", other));
+                        "no"
+                    }
+                };
+                unit.add("Service", "Restart", policy);
+                // systemd has no cap on the number of restarts
+                if !retries.is_empty() {
+                    self.warn(name, format!("restart retry count {}", retries));
+                }
+                unit.add("Install", "WantedBy", "default.target");
+            }
+        }
+
+        unit
+    }
+
+    fn networks(
+        &mut self,
+        name: &str,
+        service: &Service,
+        dependencies: &mut BTreeSet<String>,
+    ) -> Vec<String> {
+        match service.network_mode.as_deref() {
+            Some(mode @ ("host" | "none" | "private")) => return vec![mode.to_string()],
+            Some(mode) if mode.starts_with("service:") => {
+                // The pod already shares the namespace between its containers
+                if service.x_podman.as_ref().is_some_and(|x| x.pod.is_some()) {
+                    return Vec::new();
+                }
+                let target = &mode["service:".len()..];
+                dependencies.insert(target.to_string());
+                return vec![format!("{}.container", target)];
+            }
+            Some(mode) if mode.starts_with("container:") => {
+                self.warn(name, format!("network_mode {}", mode));
+                return Vec::new();
+            }
+            _ => {}
+        }
+
+        match &service.networks {
+            Some(ServiceNetworks::Simple(names)) => {
+                names.iter().map(|n| self.network_reference(n)).collect()
+            }
+            Some(ServiceNetworks::Advanced(configs)) => {
+                let mut configs: Vec<_> = configs.iter().collect();
+                configs.sort_by_key(|(network, _)| network.as_str());
+                configs
+                    .into_iter()
+                    .map(|(network, config)| {
+                        let options = self.endpoint_options(name, network, config);
+                        let reference = self.network_reference(network);
+                        if options.is_empty() {
+                            reference
+                        } else {
+                            format!("{}:{}", reference, options.join(","))
+                        }
+                    })
+                    .collect()
+            }
+            None => service
+                .network_mode
+                .iter()
+                .filter(|mode| !matches!(mode.as_str(), "default" | "bridge"))
+                .map(|mode| self.network_reference(mode))
+                .collect(),
+        }
+    }
+
+    fn network_reference(&self, network: &str) -> String {
+        if self.network_units.contains(network) {
+            format!("{}.network", network)
+        } else {
+            network.to_string()
+        }
+    }
+
+    fn endpoint_options(
+        &mut self,
+        name: &str,
+        network: &str,
+        config: &NetworkConfig,
+    ) -> Vec<String> {
+        let mut options = Vec::new();
+        options.extend(config.ipv4_address.iter().map(|ip| format!("ip={}", ip)));
+        options.extend(config.ipv6_address.iter().map(|ip| format!("ip6={}", ip)));
+        options.extend(config.mac_address.iter().map(|mac| format!("mac={}", mac)));
+        options.extend(
+            config
+                .aliases
+                .iter()
+                .flatten()
+                .map(|a| format!("alias={}", a)),
+        );
+
+        if config.link_local_ips.is_some()
+            || config.driver_opts.is_some()
+            || config.gw_priority.is_some()
+        {
+            self.warn(
+                name,
+                format!(
+                    "link-local IPs, driver options or gateway priority on network {}",
+                    network
+                ),
+            );
+        }
+        options
+    }
+
+    fn volume_spec(&self, spec: &str) -> String {
+        match spec.split_once(':') {
+            Some((source, rest)) if self.volume_units.contains(source) => {
+                format!("{}.volume:{}", source, rest)
+            }
+            _ => spec.to_string(),
+        }
+    }
+
+    fn mount_spec(&self, mount: &VolumeMount) -> Option<String> {
+        let source = mount.source.as_deref()?;
+        let mut options = Vec::new();
+        if mount.read_only == Some(true) {
+            options.push("ro".to_string());
+        }
+
+        let source = match mount.mount_type.as_str() {
+            "bind" => {
+                if let Some(bind) = &mount.bind {
+                    options.extend(bind.propagation.clone());
+                    options.extend(bind.selinux.clone());
+                }
+                source.to_string()
+            }
+            "volume" => {
+                if mount
+                    .volume
+                    .as_ref()
+                    .is_some_and(|v| v.nocopy == Some(true))
+                {
+                    options.push("nocopy".to_string());
+                }
+                if self.volume_units.contains(source) {
+                    format!("{}.volume", source)
+                } else {
+                    source.to_string()
+                }
+            }
+            _ => return None,
+        };
+
+        Some(if options.is_empty() {
+            format!("{}:{}", source, mount.target)
+        } else {
+            format!("{}:{}:{}", source, mount.target, options.join(","))
+        })
+    }
+
+    fn network_unit(&mut self, name: &str, network: &NetworkDefinition) -> UnitFile {
+        let mut unit = UnitFile::default();
+        unit.add(
+            "Network",
+            "NetworkName",
+            network.name.as_deref().unwrap_or(name),
+        );
+        if let Some(driver) = &network.driver {
+            unit.add("Network", "Driver", driver.as_str());
+        }
+        for (key, value) in sorted(network.driver_opts.as_ref()) {
+            unit.add("Network", "Options", format!("{}={}", key, value));
+        }
+        if network.internal == Some(true) {
+            unit.add("Network", "Internal", "true");
+        }
+        if network.enable_ipv6 == Some(true) {
+            unit.add("Network", "IPv6", "true");
+        }
+        if let Some(ipam) = &network.ipam {
+            if let Some(driver) = &ipam.driver {
+                unit.add("Network", "IPAMDriver", driver.as_str());
+            }
+            for pool in ipam.config.iter().flatten() {
+                if let Some(subnet) = &pool.subnet {
+                    unit.add("Network", "Subnet", subnet.as_str());
+                }
+                if let Some(gateway) = &pool.gateway {
+                    unit.add("Network", "Gateway", gateway.as_str());
+                }
+                if let Some(range) = &pool.ip_range {
+                    unit.add("Network", "IPRange", range.as_str());
+                }
+                if pool.aux_addresses.is_some() {
+                    self.warn(name, "auxiliary addresses of the network");
+                }
+            }
+        }
+        for (key, value) in sorted(network.labels.as_ref()) {
+            unit.add("Network", "Label", quote(&format!("{}={}", key, value)));
+        }
+        unit
+    }
+
+    fn volume_unit(&mut self, name: &str, volume: &VolumeDefinition) -> UnitFile {
+        let mut unit = UnitFile::default();
+        unit.add(
+            "Volume",
+            "VolumeName",
+            volume.name.as_deref().unwrap_or(name),
+        );
+        if let Some(driver) = &volume.driver {
+            unit.add("Volume", "Driver", driver.as_str());
+        }
+        for (key, value) in sorted(volume.driver_opts.as_ref()) {
+            match key.as_str() {
+                "type" => unit.add("Volume", "Type", value.as_str()),
+                "device" => unit.add("Volume", "Device", value.as_str()),
+                "o" => unit.add("Volume", "Options", value.as_str()),
+                _ => self.warn(name, format!("volume driver option {}", key)),
+            }
+        }
+        for (key, value) in sorted(volume.labels.as_ref()) {
+            unit.add("Volume", "Label", quote(&format!("{}={}", key, value)));
+        }
+        unit
+    }
+}
+
+fn is_predefined_network(name: &str) -> bool {
+    matches!(name, "podman" | "bridge" | "host" | "none")
+}
+
+fn sorted(map: Option<&HashMap<String, String>>) -> Vec<(&String, &String)> {
+    let mut entries: Vec<_> = map.into_iter().flatten().collect();
+    entries.sort();
+    entries
+}
+
+fn tmpfs_spec(mount: &VolumeMount) -> String {
+    let mut options = Vec::new();
+    if let Some(tmpfs) = &mount.tmpfs {
+        options.extend(tmpfs.size.map(|size| format!("size={}", size)));
+        options.extend(tmpfs.mode.map(|mode| format!("mode={}", mode)));
+    }
+    if options.is_empty() {
+        mount.target.clone()
+    } else {
+        format!("{}:{}", mount.target, options.join(","))
+    }
+}
+
+/// Quotes a value for a unit file when it contains spaces or quotes, and
+/// escapes the `%` specifiers systemd would otherwise expand.
+fn quote(value: &str) -> String {
+    let value = value.replace('%', "%%");
+    if !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        return value;
+    }
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A command line, with `$` escaped so systemd leaves variables to the
+/// container.
+fn exec_line(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| quote(&arg.replace('$', "$$")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compose(yaml: &str) -> ComposeFile {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn file<'a>(export: &'a QuadletExport, name: &str) -> &'a str {
+        &export
+            .files
+            .iter()
+            .find(|f| f.name == name)
+            .unwrap()
+            .contents
+    }
+
+    #[test]
+    fn test_container_unit() {
+        let export = export_quadlet(&compose(
+            r#"
+version: "3.9"
+services:
+  web:
+    image: nginx:1.25
+    platform: linux/amd64
+    ports: ["8080:80"]
+    expose: ["9113"]
+    environment:
+      GREETING: hello world
+    volumes:
+      - html:/usr/share/nginx/html:ro
+    networks: [frontend]
+    restart: unless-stopped
+    user: "101:101"
+    command: [nginx, -g, daemon off;]
+    healthcheck:
+      test: [CMD-SHELL, curl -f http://localhost/]
+      interval: 30s
+    depends_on: [api]
+    read_only: true
+    stop_grace_period: 1m
+    deploy:
+      resources:
+        limits:
+          memory: 256M
+        reservations:
+          devices:
+            - driver: nvidia
+              count: all
+              capabilities: [gpu]
+  api:
+    image: acme/api:1.4
+networks:
+  frontend:
+    driver: bridge
+volumes:
+  html: {}
+"#,
+        ));
+
+        let web = file(&export, "web.container");
+        assert!(web.contains("[Container]\nImage=nginx:1.25\n"));
+        assert!(web.contains("PublishPort=8080:80\nExposeHostPort=9113\n"));
+        assert!(web.contains("Environment=\"GREETING=hello world\"\n"));
+        assert!(web.contains("Volume=html.volume:/usr/share/nginx/html:ro\n"));
+        assert!(web.contains("Network=frontend.network\n"));
+        assert!(web.contains("User=101\nGroup=101\n"));
+        assert!(web.contains("Exec=nginx -g \"daemon off;\"\n"));
+        assert!(web.contains("HealthCmd=\"curl -f http://localhost/\"\n"));
+        assert!(web.contains("PodmanArgs=--memory=256M\n"));
+        assert!(web.contains("PodmanArgs=--platform=linux/amd64\n"));
+        assert!(web.contains("AddDevice=nvidia.com/gpu=all\n"));
+        assert!(web.contains("ReadOnly=true\nStopTimeout=60\n"));
+        assert!(web.contains("Requires=api.service\nAfter=api.service\n"));
+        assert!(web.contains("[Service]\nRestart=always\n"));
+        assert!(web.contains("[Install]\nWantedBy=default.target\n"));
+
+        assert!(file(&export, "frontend.network").contains("Driver=bridge\n"));
+        assert!(file(&export, "html.volume").contains("VolumeName=html\n"));
+        assert!(export.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_pod_units_and_warnings() {
+        let export = export_quadlet(&compose(
+            r#"
+version: "3.9"
+services:
+  web:
+    image: nginx:1.25
+    ports: ["8080:80"]
+    x-podman:
+      pod: shop
+  app:
+    image: acme/app:3
+    network_mode: service:web
+    restart: on-failure:5
+    x-podman:
+      pod: shop
+    deploy:
+      replicas: 3
+"#,
+        ));
+
+        let pod = file(&export, "shop.pod");
+        assert!(pod.contains("PodName=shop\nPublishPort=8080:80\n"));
+        let app = file(&export, "app.container");
+        assert!(app.contains("Pod=shop.pod\n"));
+        assert!(!app.contains("Network="));
+        assert!(app.contains("Restart=on-failure\n"));
+        assert!(!app.contains("StartLimitBurst"));
+        assert!(!file(&export, "web.container").contains("PublishPort"));
+        assert_eq!(export.warnings.len(), 2);
+        assert!(export.warnings[0].starts_with("app: deploy.replicas"));
+        assert_eq!(export.warnings[1], "app: restart retry count 5");
+    }
+}
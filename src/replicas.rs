@@ -0,0 +1,234 @@
+/*!
+Copyright (c) 2025 Olympus Chain SAS
+
+This software is licensed under the Olympus Chain Internal Source License (OCISL).
+You may read and modify this code for personal or internal non-commercial use only.
+Commercial use, redistribution, or reuse in other software is prohibited without prior written permission.
+
+Contact: contact@olympus-chain.fr
+*/
+
+use crate::{Deploy, Service, ServiceNetworks};
+use std::collections::HashMap;
+
+/// Settings that differ from one replica to the next and would stop compose
+/// from scaling the service.
+const PER_REPLICA_FIELDS: [&str; 2] = ["container_name", "hostname"];
+const PER_REPLICA_ENDPOINT_FIELDS: [&str; 4] = [
+    "ipv4_address",
+    "ipv6_address",
+    "mac_address",
+    "link_local_ips",
+];
+
+/// Folds containers that are replicas of one service into a single service
+/// with `deploy.replicas`. Compose labels identify replicas directly; other
+/// containers must share a numbered name (`worker-1`, `worker-2`...). Either
+/// way the replicas must be identical apart from their per-replica settings,
+/// otherwise they are kept apart with a warning.
+///
+/// Returns the service each folded container now belongs to.
+pub fn collapse_replicas(services: &mut HashMap<String, Service>) -> HashMap<String, String> {
+    let mut groups: HashMap<(String, String), Vec<String>> = HashMap::new();
+    for (name, service) in services.iter() {
+        let key = match &service.compose {
+            Some(compose) => (compose.service.clone(), compose.project.clone()),
+            None => match replica_base(name) {
+                Some(base) => (base.to_string(), String::new()),
+                None => continue,
+            },
+        };
+        groups.entry(key).or_default().push(name.clone());
+    }
+
+    let mut groups: Vec<(String, Vec<String>)> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|((base, _), mut members)| {
+            members.sort();
+            (base, members)
+        })
+        .filter(|(base, members)| {
+            let first = signature(&services[&members[0]]);
+            let identical = members[1..]
+                .iter()
+                .all(|member| signature(&services[member]) == first);
+            if !identical {
+                eprintln!(
+                    "Warning: not folding {} into replicas of {}: they differ beyond their \
+                     per-replica settings",
+                    members.join(", "),
+                    base
+                );
+            }
+            identical
+        })
+        .collect();
+    groups.sort();
+
+    let mut renames = HashMap::new();
+    for (base, mut members) in groups {
+        members.sort_by_key(|member| (replica_number(member, &services[member]), member.clone()));
+
+        let mut service = services
+            .remove(&members[0])
+            .expect("replica group members are taken from the services");
+        for member in &members[1..] {
+            services.remove(member);
+        }
+
+        strip_replica_settings(&mut service);
+        service.deploy.get_or_insert_with(Deploy::default).replicas = Some(members.len() as u32);
+
+        let name = if services.contains_key(&base) {
+            members[0].clone()
+        } else {
+            base
+        };
+        for member in members {
+            renames.insert(member, name.clone());
+        }
+        services.insert(name, service);
+    }
+
+    renames
+}
+
+/// `worker-3` and `app_worker_3` are replicas of `worker` / `app_worker`.
+fn replica_base(name: &str) -> Option<&str> {
+    let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    if base.len() == name.len() {
+        return None;
+    }
+    base.strip_suffix(['-', '_']).filter(|b| !b.is_empty())
+}
+
+fn replica_number(name: &str, service: &Service) -> u32 {
+    service
+        .compose
+        .as_ref()
+        .and_then(|c| c.container_number)
+        .or_else(|| {
+            let base = replica_base(name)?;
+            name[base.len() + 1..].parse().ok()
+        })
+        .unwrap_or(u32::MAX)
+}
+
+/// The service as it would look without its per-replica settings.
+fn signature(service: &Service) -> String {
+    let mut value = serde_json::to_value(service).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        for field in PER_REPLICA_FIELDS {
+            fields.remove(field);
+        }
+        if let Some(endpoints) = fields.get_mut("networks").and_then(|n| n.as_object_mut()) {
+            for endpoint in endpoints.values_mut().filter_map(|e| e.as_object_mut()) {
+                for field in PER_REPLICA_ENDPOINT_FIELDS {
+                    endpoint.remove(field);
+                }
+            }
+        }
+    }
+    value.to_string()
+}
+
+fn strip_replica_settings(service: &mut Service) {
+    service.container_name = None;
+    service.hostname = None;
+
+    if let Some(ServiceNetworks::Advanced(configs)) = &mut service.networks {
+        for config in configs.values_mut() {
+            config.ipv4_address = None;
+            config.ipv6_address = None;
+            config.mac_address = None;
+            config.link_local_ips = None;
+        }
+        if configs.values().all(|config| config.is_empty()) {
+            let mut names: Vec<String> = configs.keys().cloned().collect();
+            names.sort();
+            service.networks = Some(ServiceNetworks::Simple(names));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(name: &str, ip: &str) -> Service {
+        serde_yaml::from_str(&format!(
+            r#"
+image: acme/worker:2.1
+container_name: {name}
+hostname: {name}
+command: [celery, worker]
+networks:
+  backend:
+    ipv4_address: {ip}
+"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_collapse_identical_replicas() {
+        let mut services = HashMap::new();
+        for (i, ip) in ["172.20.0.11", "172.20.0.12", "172.20.0.13"]
+            .iter()
+            .enumerate()
+        {
+            let name = format!("worker-{}", i + 1);
+            services.insert(name.clone(), worker(&name, ip));
+        }
+        services.insert(
+            "redis".to_string(),
+            serde_yaml::from_str("image: redis:7").unwrap(),
+        );
+
+        let renames = collapse_replicas(&mut services);
+
+        assert_eq!(services.len(), 2);
+        let worker = &services["worker"];
+        assert_eq!(worker.deploy.as_ref().unwrap().replicas, Some(3));
+        assert!(worker.container_name.is_none() && worker.hostname.is_none());
+        assert!(matches!(&worker.networks, Some(ServiceNetworks::Simple(n)) if n == &["backend"]));
+        assert_eq!(renames["worker-2"], "worker");
+    }
+
+    #[test]
+    fn test_keep_numbered_services_that_differ() {
+        let mut services = HashMap::new();
+        services.insert(
+            "db-1".to_string(),
+            serde_yaml::from_str("image: postgres:15").unwrap(),
+        );
+        services.insert(
+            "db-2".to_string(),
+            serde_yaml::from_str("image: postgres:16").unwrap(),
+        );
+
+        assert!(collapse_replicas(&mut services).is_empty());
+        assert_eq!(services.len(), 2);
+    }
+
+    #[test]
+    fn test_keep_labelled_replicas_that_differ() {
+        let mut services = HashMap::new();
+        for (name, image) in [
+            ("shop-web-1", "shop/web:1.0"),
+            ("shop-web-2", "shop/web:1.1"),
+        ] {
+            let mut service: Service = serde_yaml::from_str(&format!("image: {}", image)).unwrap();
+            service.compose = Some(crate::projects::ComposeLabels {
+                project: "shop".to_string(),
+                service: "web".to_string(),
+                ..Default::default()
+            });
+            services.insert(name.to_string(), service);
+        }
+
+        assert!(collapse_replicas(&mut services).is_empty());
+        assert_eq!(services.len(), 2);
+    }
+}
@@ -0,0 +1,846 @@
+/*!
+Copyright (c) 2025 Olympus Chain SAS
+
+This software is licensed under the Olympus Chain Internal Source License (OCISL).
+You may read and modify this code for personal or internal non-commercial use only.
+Commercial use, redistribution, or reuse in other software is prohibited without prior written permission.
+
+Contact: contact@olympus-chain.fr
+*/
+
+use crate::{
+    kubernetes::duration_seconds, security::shell_escape, ComposeFile, DeviceCount,
+    DeviceReservation, NetworkConfig, Service, ServiceNetworks, ServiceVolume, VolumeMount,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The CLI the generated script drives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContainerEngine {
+    Docker,
+    Podman,
+}
+
+impl ContainerEngine {
+    fn command(self) -> &'static str {
+        match self {
+            ContainerEngine::Docker => "docker",
+            ContainerEngine::Podman => "podman",
+        }
+    }
+}
+
+/// A command line being assembled, one flag per line in the script.
+struct CommandLine {
+    lines: Vec<String>,
+}
+
+impl CommandLine {
+    fn new(engine: ContainerEngine, args: &[&str]) -> Self {
+        let mut words = vec![engine.command().to_string()];
+        words.extend(args.iter().map(|arg| shell_escape(arg)));
+        Self {
+            lines: vec![words.join(" ")],
+        }
+    }
+
+    fn flag(&mut self, flag: &str, value: &str) {
+        self.lines.push(format!("{} {}", flag, shell_escape(value)));
+    }
+
+    fn switch(&mut self, flag: &str) {
+        self.lines.push(flag.to_string());
+    }
+
+    fn args(&mut self, args: &[String]) {
+        let words: Vec<String> = args.iter().map(|arg| shell_escape(arg)).collect();
+        self.lines.push(words.join(" "));
+    }
+
+    fn render(&self) -> String {
+        format!("{}\n", self.lines.join(" \\\n    "))
+    }
+}
+
+/// Writes a shell script that recreates the networks, volumes and containers
+/// of `compose` with plain `run` commands, dependencies first.
+pub fn export_run_script(compose: &ComposeFile, engine: ContainerEngine) -> String {
+    let mut script = String::from("#!/bin/sh\n# Generated by autocompose\nset -e\n");
+
+    let networks = compose.network_definitions();
+    if !networks.is_empty() {
+        script.push_str("\n# Networks\n");
+    }
+    for (name, network) in &networks {
+        if network.external == Some(true) || is_predefined_network(name) {
+            script.push_str(&format!("# {} is external and must already exist\n", name));
+            continue;
+        }
+        let mut command = CommandLine::new(engine, &["network", "create"]);
+        if let Some(driver) = &network.driver {
+            command.flag("--driver", driver);
+        }
+        for (key, value) in sorted(network.driver_opts.as_ref()) {
+            command.flag("--opt", &format!("{}={}", key, value));
+        }
+        if let Some(ipam) = &network.ipam {
+            if let Some(driver) = &ipam.driver {
+                command.flag("--ipam-driver", driver);
+            }
+            for pool in ipam.config.iter().flatten() {
+                if let Some(subnet) = &pool.subnet {
+                    command.flag("--subnet", subnet);
+                }
+                if let Some(gateway) = &pool.gateway {
+                    command.flag("--gateway", gateway);
+                }
+                if let Some(range) = &pool.ip_range {
+                    command.flag("--ip-range", range);
+                }
+            }
+        }
+        if network.internal == Some(true) {
+            command.switch("--internal");
+        }
+        if network.enable_ipv6 == Some(true) {
+            command.switch("--ipv6");
+        }
+        for (key, value) in sorted(network.labels.as_ref()) {
+            command.flag("--label", &format!("{}={}", key, value));
+        }
+        command.args(&[network.name.clone().unwrap_or_else(|| name.clone())]);
+        script.push_str(&command.render());
+    }
+
+    let volumes = compose.volume_definitions();
+    if !volumes.is_empty() {
+        script.push_str("\n# Volumes\n");
+    }
+    for (name, volume) in &volumes {
+        if volume.external == Some(true) {
+            script.push_str(&format!("# {} is external and must already exist\n", name));
+            continue;
+        }
+        let mut command = CommandLine::new(engine, &["volume", "create"]);
+        if let Some(driver) = &volume.driver {
+            command.flag("--driver", driver);
+        }
+        for (key, value) in sorted(volume.driver_opts.as_ref()) {
+            command.flag("--opt", &format!("{}={}", key, value));
+        }
+        for (key, value) in sorted(volume.labels.as_ref()) {
+            command.flag("--label", &format!("{}={}", key, value));
+        }
+        command.args(&[volume.name.clone().unwrap_or_else(|| name.clone())]);
+        script.push_str(&command.render());
+    }
+
+    let pods = if engine == ContainerEngine::Podman {
+        pods(compose)
+    } else {
+        BTreeMap::new()
+    };
+    if !pods.is_empty() {
+        script.push_str("\n# Pods\n");
+    }
+    for (pod, settings) in &pods {
+        let mut command = CommandLine::new(engine, &["pod", "create"]);
+        command.flag("--name", pod);
+        for port in &settings.ports {
+            command.flag("--publish", port);
+        }
+        for network in &settings.networks {
+            command.flag("--network", network);
+        }
+        script.push_str(&command.render());
+    }
+
+    for name in start_order(&compose.services) {
+        let service = &compose.services[name];
+        script.push_str(&format!("\n# {}\n", name));
+        if let Some(replicas) = service.deploy.as_ref().and_then(|d| d.replicas) {
+            if replicas > 1 {
+                script.push_str(&format!(
+                    "# deploy.replicas: {}; run once per replica with a distinct --name\n",
+                    replicas
+                ));
+            }
+        }
+        script.push_str(&run_command(name, service, &compose.services, engine));
+
+        // `run` joins a single network, the others are connected afterwards.
+        // Pod members are already on the networks of their pod.
+        if pod_of(service, engine).is_some() {
+            continue;
+        }
+        if let Some(ServiceNetworks::Advanced(configs)) = &service.networks {
+            for (network, config) in sorted_networks(configs).into_iter().skip(1) {
+                let mut command = CommandLine::new(engine, &["network", "connect"]);
+                endpoint_flags(&mut command, config);
+                command.args(&[network.clone(), container_name(name, service)]);
+                script.push_str(&command.render());
+            }
+        } else if let Some(ServiceNetworks::Simple(networks)) = &service.networks {
+            for network in networks.iter().skip(1) {
+                let command = CommandLine::new(
+                    engine,
+                    &[
+                        "network",
+                        "connect",
+                        network,
+                        &container_name(name, service),
+                    ],
+                );
+                script.push_str(&command.render());
+            }
+        }
+    }
+
+    script
+}
+
+fn run_command(
+    name: &str,
+    service: &Service,
+    services: &HashMap<String, Service>,
+    engine: ContainerEngine,
+) -> String {
+    let mut command = CommandLine::new(engine, &["run", "--detach"]);
+    command.flag("--name", &container_name(name, service));
+
+    let pod = pod_of(service, engine);
+    if let Some(pod) = pod {
+        command.flag("--pod", pod);
+    }
+    if let Some(platform) = &service.platform {
+        command.flag("--platform", platform);
+    }
+    if let Some(hostname) = &service.hostname {
+        command.flag("--hostname", hostname);
+    }
+
+    for (key, value) in sorted(service.environment.as_ref()) {
+        command.flag("--env", &format!("{}={}", key, value));
+    }
+    for (key, value) in sorted(service.labels.as_ref()) {
+        command.flag("--label", &format!("{}={}", key, value));
+    }
+    // Members of a pod publish their ports on the pod
+    if pod.is_none() {
+        for port in service.ports.iter().flatten() {
+            command.flag("--publish", port);
+        }
+    }
+    for port in service.expose.iter().flatten() {
+        command.flag("--expose", port);
+    }
+
+    for volume in service.volumes.iter().flatten() {
+        match volume {
+            ServiceVolume::Short(spec) => command.flag("--volume", spec),
+            ServiceVolume::Long(mount) => command.flag("--mount", &mount_spec(mount)),
+        }
+    }
+    for tmpfs in service.tmpfs.iter().flatten() {
+        command.flag("--tmpfs", tmpfs);
+    }
+
+    // Pod members use the pod's network namespace
+    match service.network_mode.as_deref() {
+        _ if pod.is_some() => {}
+        Some(mode) if mode.starts_with("service:") => {
+            command.flag("--network", &namespace_target(mode, services));
+        }
+        Some(mode) if service.networks.is_none() => command.flag("--network", mode),
+        _ => {}
+    }
+    match &service.networks {
+        _ if pod.is_some() => {}
+        Some(ServiceNetworks::Simple(networks)) => {
+            if let Some(network) = networks.first() {
+                command.flag("--network", network);
+            }
+        }
+        Some(ServiceNetworks::Advanced(configs)) => {
+            if let Some((network, config)) = sorted_networks(configs).first() {
+                command.flag("--network", network);
+                endpoint_flags(&mut command, config);
+            }
+        }
+        None => {}
+    }
+
+    for dns in service.dns.iter().flatten() {
+        command.flag("--dns", dns);
+    }
+    for search in service.dns_search.iter().flatten() {
+        command.flag("--dns-search", search);
+    }
+    for host in service.extra_hosts.iter().flatten() {
+        command.flag("--add-host", host);
+    }
+    if let Some(restart) = &service.restart {
+        command.flag("--restart", restart);
+    }
+    for cap in service.cap_add.iter().flatten() {
+        command.flag("--cap-add", cap);
+    }
+    for cap in service.cap_drop.iter().flatten() {
+        command.flag("--cap-drop", cap);
+    }
+    for opt in service.security_opt.iter().flatten() {
+        command.flag("--security-opt", opt);
+    }
+    for (flag, value) in [
+        ("--pid", &service.pid),
+        ("--ipc", &service.ipc),
+        ("--uts", &service.uts),
+        ("--userns", &service.userns_mode),
+        ("--cgroupns", &service.cgroup),
+        ("--cgroup-parent", &service.cgroup_parent),
+        ("--isolation", &service.isolation),
+        ("--runtime", &service.runtime),
+    ] {
+        if let Some(value) = value {
+            command.flag(flag, &namespace_target(value, services));
+        }
+    }
+
+    if let Some(healthcheck) = &service.healthcheck {
+        match healthcheck.test.split_first() {
+            Some((kind, _)) if kind == "NONE" => command.switch("--no-healthcheck"),
+            Some((kind, args)) => {
+                let cmd = if kind == "CMD" {
+                    let words: Vec<String> = args.iter().map(|arg| shell_escape(arg)).collect();
+                    words.join(" ")
+                } else {
+                    args.join(" ")
+                };
+                command.flag("--health-cmd", &cmd);
+            }
+            None => {}
+        }
+        if let Some(interval) = &healthcheck.interval {
+            command.flag("--health-interval", interval);
+        }
+        if let Some(timeout) = &healthcheck.timeout {
+            command.flag("--health-timeout", timeout);
+        }
+        if let Some(retries) = healthcheck.retries {
+            command.flag("--health-retries", &retries.to_string());
+        }
+        if let Some(start_period) = &healthcheck.start_period {
+            command.flag("--health-start-period", start_period);
+        }
+    }
+
+    if let Some(logging) = &service.logging {
+        if !logging.driver.is_empty() {
+            command.flag("--log-driver", &logging.driver);
+        }
+        for (key, value) in sorted(logging.options.as_ref()) {
+            command.flag("--log-opt", &format!("{}={}", key, value));
+        }
+    }
+    for device in service.devices.iter().flatten() {
+        command.flag("--device", device);
+    }
+    for gpu in gpus(service) {
+        match engine {
+            ContainerEngine::Docker => command.flag("--gpus", &gpus_value(gpu)),
+            ContainerEngine::Podman => {
+                for device in cdi_gpus(gpu) {
+                    command.flag("--device", &device);
+                }
+            }
+        }
+    }
+    if let Some(user) = &service.user {
+        command.flag("--user", user);
+    }
+    if let Some(dir) = &service.working_dir {
+        command.flag("--workdir", dir);
+    }
+
+    let mut ulimits: Vec<_> = service.ulimits.iter().flatten().collect();
+    ulimits.sort_by_key(|(name, _)| name.as_str());
+    for (ulimit, limit) in ulimits {
+        command.flag(
+            "--ulimit",
+            &format!("{}={}:{}", ulimit, limit.soft, limit.hard),
+        );
+    }
+    for (key, value) in sorted(service.sysctls.as_ref()) {
+        command.flag("--sysctl", &format!("{}={}", key, value));
+    }
+    if service.init == Some(true) {
+        command.switch("--init");
+    }
+    if service.privileged == Some(true) {
+        command.switch("--privileged");
+    }
+    if service.tty == Some(true) {
+        command.switch("--tty");
+    }
+    if service.stdin_open == Some(true) {
+        command.switch("--interactive");
+    }
+    if service.read_only == Some(true) {
+        command.switch("--read-only");
+    }
+    if let Some(signal) = &service.stop_signal {
+        command.flag("--stop-signal", signal);
+    }
+    if let Some(seconds) = service
+        .stop_grace_period
+        .as_deref()
+        .and_then(duration_seconds)
+    {
+        command.flag("--stop-timeout", &seconds.to_string());
+    }
+    for group in service.group_add.iter().flatten() {
+        command.flag("--group-add", group);
+    }
+    if let Some(domainname) = &service.domainname {
+        command.flag("--domainname", domainname);
+    }
+    if let Some(mac_address) = &service.mac_address {
+        command.flag("--mac-address", mac_address);
+    }
+    for (key, value) in sorted(service.storage_opt.as_ref()) {
+        command.flag("--storage-opt", &format!("{}={}", key, value));
+    }
+    for rule in service.device_cgroup_rules.iter().flatten() {
+        command.flag("--device-cgroup-rule", rule);
+    }
+    for (key, value) in sorted(service.annotations.as_ref()) {
+        command.flag("--annotation", &format!("{}={}", key, value));
+    }
+    for (flag, value) in resource_flags(service) {
+        match value {
+            Some(value) => command.flag(flag, &value),
+            None => command.switch(flag),
+        }
+    }
+
+    // --entrypoint only takes the executable; its arguments lead the command
+    let mut args = Vec::new();
+    if let Some((executable, entrypoint_args)) =
+        service.entrypoint.as_ref().and_then(|e| e.split_first())
+    {
+        command.flag("--entrypoint", executable);
+        args.extend(entrypoint_args.iter().cloned());
+    }
+    args.insert(0, service.image.clone());
+    args.extend(service.command.iter().flatten().cloned());
+    command.args(&args);
+
+    command.render()
+}
+
+/// The `run` flags for the service's resource controls. Switches have no value.
+pub(crate) fn resource_flags(service: &Service) -> Vec<(&'static str, Option<String>)> {
+    let mut flags = Vec::new();
+    let resources = service.deploy.as_ref().and_then(|d| d.resources.as_ref());
+    if let Some(limits) = resources.and_then(|r| r.limits.as_ref()) {
+        flags.push(("--memory", limits.memory.clone()));
+        flags.push(("--cpus", limits.cpus.clone()));
+    }
+    if let Some(reservations) = resources.and_then(|r| r.reservations.as_ref()) {
+        flags.push(("--memory-reservation", reservations.memory.clone()));
+    }
+    let number = |value: Option<i64>| value.map(|v| v.to_string());
+    flags.extend([
+        ("--memory-swap", service.memswap_limit.clone()),
+        ("--memory-swappiness", number(service.mem_swappiness)),
+        ("--cpu-shares", number(service.cpu_shares)),
+        ("--cpu-period", number(service.cpu_period)),
+        ("--cpu-quota", number(service.cpu_quota)),
+        ("--cpuset-cpus", service.cpuset.clone()),
+        ("--pids-limit", number(service.pids_limit)),
+        ("--oom-score-adj", number(service.oom_score_adj)),
+        ("--shm-size", service.shm_size.clone()),
+    ]);
+    let mut flags: Vec<_> = flags
+        .into_iter()
+        .filter_map(|(flag, value)| Some((flag, Some(value?))))
+        .collect();
+    if service.oom_kill_disable == Some(true) {
+        flags.push(("--oom-kill-disable", None));
+    }
+
+    if let Some(blkio) = &service.blkio_config {
+        if let Some(weight) = blkio.weight {
+            flags.push(("--blkio-weight", Some(weight.to_string())));
+        }
+        for device in blkio.weight_device.iter().flatten() {
+            let value = format!("{}:{}", device.path, device.weight);
+            flags.push(("--blkio-weight-device", Some(value)));
+        }
+        for (flag, throttles) in [
+            ("--device-read-bps", &blkio.device_read_bps),
+            ("--device-write-bps", &blkio.device_write_bps),
+            ("--device-read-iops", &blkio.device_read_iops),
+            ("--device-write-iops", &blkio.device_write_iops),
+        ] {
+            for throttle in throttles.iter().flatten() {
+                flags.push((flag, Some(format!("{}:{}", throttle.path, throttle.rate))));
+            }
+        }
+    }
+    flags
+}
+
+/// True for a device reservation that `--gpus` can express.
+pub(crate) fn is_gpu(device: &DeviceReservation) -> bool {
+    device.capabilities.iter().any(|c| c == "gpu")
+        && device.driver.as_deref().unwrap_or("nvidia") == "nvidia"
+}
+
+/// The GPU reservations of a service.
+pub(crate) fn gpus(service: &Service) -> impl Iterator<Item = &DeviceReservation> {
+    service
+        .deploy
+        .as_ref()
+        .and_then(|d| d.resources.as_ref())
+        .and_then(|r| r.reservations.as_ref())
+        .and_then(|r| r.devices.as_ref())
+        .into_iter()
+        .flatten()
+        .filter(|device| is_gpu(device))
+}
+
+/// The CDI names Podman gives the GPUs of a reservation; a count takes the first ones.
+pub(crate) fn cdi_gpus(device: &DeviceReservation) -> Vec<String> {
+    let ids: Vec<String> = match (&device.count, &device.device_ids) {
+        (_, Some(ids)) => ids.clone(),
+        (Some(DeviceCount::Count(count)), None) => (0..*count).map(|i| i.to_string()).collect(),
+        _ => vec!["all".to_string()],
+    };
+    ids.into_iter()
+        .map(|id| format!("nvidia.com/gpu={}", id))
+        .collect()
+}
+
+/// The `--gpus` value for a GPU reservation, e.g. `all` or `count=2`.
+fn gpus_value(device: &DeviceReservation) -> String {
+    let mut fields = Vec::new();
+    // Compose reserves every GPU when neither a count nor IDs are given
+    match (&device.count, &device.device_ids) {
+        (Some(DeviceCount::Count(count)), _) => fields.push(format!("count={}", count)),
+        (Some(DeviceCount::All(_)), _) | (None, None) => fields.push("count=all".to_string()),
+        (None, Some(_)) => {}
+    }
+    // Lists are quoted since the value itself is comma-separated
+    if let Some(ids) = &device.device_ids {
+        fields.push(format!("\"device={}\"", ids.join(",")));
+    }
+    if device.capabilities.len() > 1 {
+        let capabilities = device.capabilities.join(",");
+        fields.push(format!("\"capabilities={}\"", capabilities));
+    }
+    match fields.as_slice() {
+        [field] if field == "count=all" => "all".to_string(),
+        _ => fields.join(","),
+    }
+}
+
+/// `service:<name>` only means something to compose; the engines want the
+/// container behind it.
+fn namespace_target(mode: &str, services: &HashMap<String, Service>) -> String {
+    match mode.strip_prefix("service:") {
+        Some(target) => match services.get(target) {
+            Some(service) => format!("container:{}", container_name(target, service)),
+            None => format!("container:{}", target),
+        },
+        None => mode.to_string(),
+    }
+}
+
+fn container_name(name: &str, service: &Service) -> String {
+    service
+        .container_name
+        .clone()
+        .unwrap_or_else(|| name.to_string())
+}
+
+fn endpoint_flags(command: &mut CommandLine, config: &NetworkConfig) {
+    if let Some(ip) = &config.ipv4_address {
+        command.flag("--ip", ip);
+    }
+    if let Some(ip) = &config.ipv6_address {
+        command.flag("--ip6", ip);
+    }
+    if let Some(mac) = &config.mac_address {
+        command.flag("--mac-address", mac);
+    }
+    for alias in config.aliases.iter().flatten() {
+        command.flag("--network-alias", alias);
+    }
+}
+
+fn mount_spec(mount: &VolumeMount) -> String {
+    let mut options = vec![format!("type={}", mount.mount_type)];
+    if let Some(source) = &mount.source {
+        options.push(format!("source={}", source));
+    }
+    options.push(format!("target={}", mount.target));
+    if mount.read_only == Some(true) {
+        options.push("readonly".to_string());
+    }
+    if let Some(propagation) = mount.bind.as_ref().and_then(|b| b.propagation.as_ref()) {
+        options.push(format!("bind-propagation={}", propagation));
+    }
+    if mount
+        .volume
+        .as_ref()
+        .is_some_and(|v| v.nocopy == Some(true))
+    {
+        options.push("volume-nocopy".to_string());
+    }
+    if let Some(subpath) = mount.volume.as_ref().and_then(|v| v.subpath.as_ref()) {
+        options.push(format!("volume-subpath={}", subpath));
+    }
+    if let Some(tmpfs) = &mount.tmpfs {
+        if let Some(size) = tmpfs.size {
+            options.push(format!("tmpfs-size={}", size));
+        }
+        if let Some(mode) = tmpfs.mode {
+            options.push(format!("tmpfs-mode={}", mode));
+        }
+    }
+    options.join(",")
+}
+
+#[derive(Default)]
+struct PodSettings {
+    ports: Vec<String>,
+    networks: Vec<String>,
+}
+
+/// Pods of the `x-podman` metadata with the ports their members publish and
+/// the networks they join.
+fn pods(compose: &ComposeFile) -> BTreeMap<String, PodSettings> {
+    let mut pods: BTreeMap<String, PodSettings> = BTreeMap::new();
+    for service in compose.services.values() {
+        if let Some(pod) = service.x_podman.as_ref().and_then(|x| x.pod.clone()) {
+            let settings = pods.entry(pod).or_default();
+            settings
+                .ports
+                .extend(service.ports.iter().flatten().cloned());
+            match &service.networks {
+                Some(ServiceNetworks::Simple(networks)) => {
+                    settings.networks.extend(networks.iter().cloned())
+                }
+                Some(ServiceNetworks::Advanced(configs)) => {
+                    settings.networks.extend(configs.keys().cloned())
+                }
+                None => {}
+            }
+        }
+    }
+    for settings in pods.values_mut() {
+        settings.ports.sort();
+        settings.ports.dedup();
+        settings.networks.sort();
+        settings.networks.dedup();
+    }
+    pods
+}
+
+/// The pod a service joins, which only Podman knows about.
+fn pod_of(service: &Service, engine: ContainerEngine) -> Option<&str> {
+    service
+        .x_podman
+        .as_ref()
+        .and_then(|x| x.pod.as_deref())
+        .filter(|_| engine == ContainerEngine::Podman)
+}
+
+/// Service names with every dependency before its dependents.
+fn start_order(services: &HashMap<String, Service>) -> Vec<&str> {
+    fn visit<'a>(
+        name: &'a str,
+        services: &'a HashMap<String, Service>,
+        visited: &mut BTreeSet<&'a str>,
+        order: &mut Vec<&'a str>,
+    ) {
+        if !visited.insert(name) {
+            return;
+        }
+        if let Some(service) = services.get(name) {
+            let mut dependencies = service
+                .depends_on
+                .as_ref()
+                .map(|d| d.services())
+                .unwrap_or_default();
+            if let Some(target) = service
+                .network_mode
+                .as_deref()
+                .and_then(|m| m.strip_prefix("service:"))
+            {
+                dependencies.push(target);
+            }
+            for dependency in dependencies {
+                if let Some((key, _)) = services.get_key_value(dependency) {
+                    visit(key, services, visited, order);
+                }
+            }
+        }
+        order.push(name);
+    }
+
+    let mut names: Vec<&str> = services.keys().map(|s| s.as_str()).collect();
+    names.sort_unstable();
+    let mut visited = BTreeSet::new();
+    let mut order = Vec::new();
+    for name in names {
+        visit(name, services, &mut visited, &mut order);
+    }
+    order
+}
+
+fn sorted_networks(configs: &HashMap<String, NetworkConfig>) -> Vec<(&String, &NetworkConfig)> {
+    let mut networks: Vec<_> = configs.iter().collect();
+    networks.sort_by_key(|(name, _)| name.as_str());
+    networks
+}
+
+fn sorted(map: Option<&HashMap<String, String>>) -> Vec<(&String, &String)> {
+    let mut entries: Vec<_> = map.into_iter().flatten().collect();
+    entries.sort();
+    entries
+}
+
+fn is_predefined_network(name: &str) -> bool {
+    matches!(name, "podman" | "bridge" | "host" | "none")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_script() {
+        let compose: ComposeFile = serde_yaml::from_str(
+            r#"
+version: "3.9"
+services:
+  web:
+    image: nginx:1.25
+    ports: ["8080:80"]
+    environment:
+      GREETING: hello world
+    networks:
+      frontend:
+        aliases: [www]
+      backend: {}
+    command: [nginx, -g, daemon off;]
+    depends_on: [api]
+  api:
+    image: acme/api:1.4
+    platform: linux/arm64
+    expose: ["9090"]
+    entrypoint: [/bin/sh, -c]
+    command: ["exec api --port $PORT"]
+    cpuset: "0,1"
+    oom_kill_disable: true
+    deploy:
+      resources:
+        reservations:
+          devices:
+            - capabilities: [gpu]
+              device_ids: ["0", "2"]
+    blkio_config:
+      device_write_bps:
+        - path: /dev/sda
+          rate: 1048576
+networks:
+  frontend: {}
+  backend:
+    external: true
+volumes:
+  data:
+    driver: local
+"#,
+        )
+        .unwrap();
+
+        let script = export_run_script(&compose, ContainerEngine::Podman);
+
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("podman network create \\\n    frontend\n"));
+        assert!(script.contains("# backend is external and must already exist\n"));
+        assert!(script.contains("podman volume create \\\n    --driver local \\\n    data\n"));
+        assert!(script.find("# api\n").unwrap() < script.find("# web\n").unwrap());
+        assert!(script.contains("--env 'GREETING=hello world'"));
+        assert!(script.contains("--network backend \\\n"));
+        assert!(script
+            .contains("podman network connect \\\n    --network-alias www \\\n    frontend web\n"));
+        assert!(script
+            .contains("--entrypoint /bin/sh \\\n    acme/api:1.4 -c 'exec api --port $PORT'\n"));
+        assert!(script.contains("nginx:1.25 nginx -g 'daemon off;'\n"));
+        assert!(script.contains("--cpuset-cpus 0,1 \\\n    --oom-kill-disable \\\n"));
+        assert!(script.contains("--device-write-bps /dev/sda:1048576 \\\n"));
+        assert!(script.contains("--name api \\\n    --platform linux/arm64 \\\n"));
+        assert!(script.contains("--expose 9090 \\\n"));
+        assert!(script.contains("--device 'nvidia.com/gpu=0' \\\n"));
+        assert!(script.contains("--device 'nvidia.com/gpu=2' \\\n"));
+    }
+
+    #[test]
+    fn test_pod_networks() {
+        let compose: ComposeFile = serde_yaml::from_str(
+            r#"
+version: "3.9"
+services:
+  web:
+    image: nginx:1.25
+    ports: ["8080:80"]
+    networks: [frontend, backend]
+    x-podman:
+      pod: shop
+  app:
+    image: acme/app:3
+    networks:
+      backend:
+        aliases: [api]
+    x-podman:
+      pod: shop
+networks:
+  frontend: {}
+  backend: {}
+"#,
+        )
+        .unwrap();
+
+        let script = export_run_script(&compose, ContainerEngine::Podman);
+
+        assert!(script.contains(
+            "podman pod create \\\n    --name shop \\\n    --publish 8080:80 \\\n    \
+             --network backend \\\n    --network frontend\n"
+        ));
+        assert!(!script.contains("network connect"));
+        let runs = &script[script.find("# app\n").unwrap()..];
+        assert!(runs.contains("--pod shop"));
+        assert!(!runs.contains("--network"));
+    }
+
+    #[test]
+    fn test_gpus_value() {
+        let gpu = |yaml: &str| -> DeviceReservation { serde_yaml::from_str(yaml).unwrap() };
+
+        assert_eq!(gpus_value(&gpu("capabilities: [gpu]")), "all");
+        assert_eq!(gpus_value(&gpu("count: 2\ncapabilities: [gpu]")), "count=2");
+        let ids = gpu("device_ids: ['0', '3']\ncapabilities: [gpu, utility]");
+        assert_eq!(
+            gpus_value(&ids),
+            r#""device=0,3","capabilities=gpu,utility""#
+        );
+        assert_eq!(
+            cdi_gpus(&gpu("count: 2\ncapabilities: [gpu]")),
+            vec!["nvidia.com/gpu=0", "nvidia.com/gpu=1"]
+        );
+        assert!(!is_gpu(&gpu("driver: amd\ncapabilities: [gpu]")));
+    }
+}
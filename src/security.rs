@@ -51,6 +51,28 @@ pub fn validate_image_id(image: &str) -> Result<&str> {
     Ok(image)
 }
 
+/// Validates that a volume or network name is safe to use in shell commands
+pub fn validate_resource_name(name: &str) -> Result<&str> {
+    if name.is_empty() || name.len() > MAX_IMAGE_ID_LENGTH {
+        return Err(AutoComposeError::Validation(
+            "Invalid resource name length".to_string(),
+        ));
+    }
+
+    // Volume and network names follow [a-zA-Z0-9][a-zA-Z0-9_.-]*
+    if !name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_')
+    {
+        return Err(AutoComposeError::Validation(
+            "Resource name contains invalid characters".to_string(),
+        ));
+    }
+
+    Ok(name)
+}
+
 /// Validates output file path to prevent directory traversal
 pub fn validate_output_path(path: &Path) -> Result<PathBuf> {
     // If path has no parent, use current directory
@@ -86,8 +108,8 @@ pub fn validate_output_path(path: &Path) -> Result<PathBuf> {
     Ok(canonical)
 }
 
-/// Filters out sensitive environment variables
-pub fn filter_sensitive_env_vars(env_vars: HashMap<String, String>) -> HashMap<String, String> {
+/// Whether an environment variable name looks like it holds a credential
+pub fn is_sensitive_env_var(key: &str) -> bool {
     lazy_static::lazy_static! {
         static ref SENSITIVE_PATTERNS: HashSet<&'static str> = {
             let mut set = HashSet::new();
@@ -113,14 +135,17 @@ pub fn filter_sensitive_env_vars(env_vars: HashMap<String, String>) -> HashMap<S
         };
     }
 
+    let key_upper = key.to_uppercase();
+    SENSITIVE_PATTERNS
+        .iter()
+        .any(|pattern| key_upper.contains(pattern))
+}
+
+/// Filters out sensitive environment variables
+pub fn filter_sensitive_env_vars(env_vars: HashMap<String, String>) -> HashMap<String, String> {
     env_vars
         .into_iter()
-        .filter(|(key, _)| {
-            let key_upper = key.to_uppercase();
-            !SENSITIVE_PATTERNS
-                .iter()
-                .any(|pattern| key_upper.contains(pattern))
-        })
+        .filter(|(key, _)| !is_sensitive_env_var(key))
         .collect()
 }
 
@@ -153,6 +178,15 @@ mod tests {
         assert!(validate_image_id("").is_err());
     }
 
+    #[test]
+    fn test_validate_resource_name() {
+        assert!(validate_resource_name("app_data").is_ok());
+        assert!(validate_resource_name("proxy-net.v2").is_ok());
+        assert!(validate_resource_name("-rm").is_err());
+        assert!(validate_resource_name("data;rm -rf /").is_err());
+        assert!(validate_resource_name("").is_err());
+    }
+
     #[test]
     fn test_filter_sensitive_env_vars() {
         let mut env = HashMap::new();
@@ -0,0 +1,702 @@
+/*!
+Copyright (c) 2025 Olympus Chain SAS
+
+This software is licensed under the Olympus Chain Internal Source License (OCISL).
+You may read and modify this code for personal or internal non-commercial use only.
+Commercial use, redistribution, or reuse in other software is prohibited without prior written permission.
+
+Contact: contact@olympus-chain.fr
+*/
+
+use crate::{
+    docker::DockerProcessor, filter_system_labels, format_size_bytes, normalize_duration_from_ns,
+    projects::ComposeLabels, sanitize_service_name, security::filter_sensitive_env_vars, Deploy,
+    FileReference, Logging, NetworkConfig, Placement, PlacementPreference, ResourceLimits,
+    ResourceReservations, Resources, RestartPolicy, Service, ServiceNetworks, ServiceVolume,
+    UlimitConfig, UpdateConfig,
+};
+use bollard::models::{
+    EndpointPortConfigPublishModeEnum, EndpointSpecModeEnum, MountTypeEnum, ServiceSpec,
+    ServiceSpecMode, TaskSpecRestartPolicy,
+};
+use serde_yaml::Value;
+use std::collections::HashMap;
+
+/// Label `docker stack deploy` puts on everything it creates.
+pub const STACK_NAMESPACE_LABEL: &str = "com.docker.stack.namespace";
+
+/// Swarm fills these in when a service is created without them.
+const DEFAULT_MONITOR_NS: i64 = 5_000_000_000;
+const DEFAULT_RESTART_DELAY_NS: i64 = 5_000_000_000;
+const DEFAULT_FILE_MODE: u32 = 0o444;
+
+/// The services of a Swarm, as the pieces of a stack file.
+#[derive(Debug, Default)]
+pub struct SwarmStack {
+    pub services: HashMap<String, Service>,
+    pub networks: HashMap<String, Value>,
+    pub volumes: HashMap<String, Value>,
+    pub secrets: HashMap<String, Value>,
+    pub configs: HashMap<String, Value>,
+}
+
+/// Top-level entry for a secret or config that must already exist.
+pub fn external_definition() -> Value {
+    let mut definition = serde_yaml::Mapping::new();
+    definition.insert(Value::from("external"), Value::from(true));
+    Value::Mapping(definition)
+}
+
+/// A Swarm service converted to a compose service.
+#[derive(Debug)]
+pub struct StackService {
+    pub name: String,
+    pub stack: Option<String>,
+    pub service: Service,
+    pub networks: Vec<String>,
+    pub volumes: Vec<String>,
+}
+
+/// Builds the compose service for a Swarm service spec. `network_names` maps
+/// the network IDs the spec refers to onto their names.
+pub fn stack_service(
+    spec: &ServiceSpec,
+    network_names: &HashMap<String, String>,
+    include_sensitive: bool,
+    pin_digests: bool,
+) -> Option<StackService> {
+    let full_name = spec.name.clone()?;
+    let task = spec.task_template.as_ref()?;
+    let container = task.container_spec.as_ref()?;
+
+    let stack = spec
+        .labels
+        .as_ref()
+        .and_then(|labels| labels.get(STACK_NAMESPACE_LABEL))
+        .filter(|stack| !stack.is_empty())
+        .cloned();
+    let short_name = stack
+        .as_ref()
+        .and_then(|stack| full_name.strip_prefix(&format!("{}_", stack)))
+        .unwrap_or(&full_name)
+        .to_string();
+
+    // Swarm resolves tags to a digest when the service is created
+    let image = container.image.clone().unwrap_or_default();
+    let image = match image.split_once("@sha256:") {
+        Some((reference, _)) if !pin_digests && !reference.is_empty() => reference.to_string(),
+        _ => image,
+    };
+
+    let environment = container.env.as_ref().map(|env| {
+        let vars: HashMap<String, String> = env
+            .iter()
+            .map(|var| match var.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (var.clone(), String::new()),
+            })
+            .collect();
+        if include_sensitive {
+            vars
+        } else {
+            filter_sensitive_env_vars(vars)
+        }
+    });
+
+    let mut volumes = Vec::new();
+    let mut volume_names = Vec::new();
+    let mut tmpfs = Vec::new();
+    for mount in container.mounts.iter().flatten() {
+        let Some(target) = mount.target.as_deref() else {
+            continue;
+        };
+        let suffix = if mount.read_only == Some(true) {
+            ":ro"
+        } else {
+            ""
+        };
+        match (mount.typ, mount.source.as_deref()) {
+            (Some(MountTypeEnum::TMPFS), _) => tmpfs.push(target.to_string()),
+            (Some(MountTypeEnum::VOLUME), Some(source)) if !source.is_empty() => {
+                volume_names.push(source.to_string());
+                volumes.push(ServiceVolume::Short(format!(
+                    "{}:{}{}",
+                    source, target, suffix
+                )));
+            }
+            (Some(MountTypeEnum::BIND), Some(source)) => {
+                volumes.push(ServiceVolume::Short(format!(
+                    "{}:{}{}",
+                    source, target, suffix
+                )));
+            }
+            // Anonymous volumes are recreated from the target alone
+            (Some(MountTypeEnum::VOLUME), _) => {
+                volumes.push(ServiceVolume::Short(target.to_string()))
+            }
+            _ => {}
+        }
+    }
+
+    let attachments = task.networks.as_ref().or(spec.networks.as_ref());
+    let mut networks = Vec::new();
+    let mut endpoints = HashMap::new();
+    for attachment in attachments.into_iter().flatten() {
+        let Some(target) = attachment.target.as_deref() else {
+            continue;
+        };
+        let network = network_names
+            .get(target)
+            .cloned()
+            .unwrap_or_else(|| target.to_string());
+        // `docker stack deploy` aliases every service by its short name
+        let aliases: Vec<String> = attachment
+            .aliases
+            .iter()
+            .flatten()
+            .filter(|alias| **alias != short_name && **alias != full_name)
+            .cloned()
+            .collect();
+        networks.push(network.clone());
+        endpoints.insert(
+            network,
+            NetworkConfig {
+                aliases: Some(aliases).filter(|a| !a.is_empty()),
+                driver_opts: attachment.driver_opts.clone().filter(|o| !o.is_empty()),
+                ..Default::default()
+            },
+        );
+    }
+    let service_networks = if endpoints.is_empty() {
+        None
+    } else if endpoints.values().all(|endpoint| endpoint.is_empty()) {
+        let mut names = networks.clone();
+        names.sort();
+        Some(ServiceNetworks::Simple(names))
+    } else {
+        Some(ServiceNetworks::Advanced(endpoints))
+    };
+
+    let ports: Vec<String> = spec
+        .endpoint_spec
+        .as_ref()
+        .and_then(|endpoint| endpoint.ports.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|port| {
+            let target = port.target_port?;
+            let protocol = port
+                .protocol
+                .map(|p| p.to_string())
+                .filter(|p| !p.is_empty() && p != "tcp")
+                .map(|p| format!("/{}", p))
+                .unwrap_or_default();
+            if port.publish_mode == Some(EndpointPortConfigPublishModeEnum::HOST) {
+                eprintln!(
+                    "Warning: {} publishes port {} in host mode, exported as an ingress port",
+                    full_name, target
+                );
+            }
+            Some(match port.published_port.filter(|p| *p > 0) {
+                Some(published) => format!("{}:{}{}", published, target, protocol),
+                None => format!("{}{}", target, protocol),
+            })
+        })
+        .collect();
+
+    let secrets: Vec<FileReference> = container
+        .secrets
+        .iter()
+        .flatten()
+        .filter_map(|secret| {
+            let source = secret.secret_name.clone()?;
+            let file = secret.file.as_ref();
+            Some(file_reference(
+                source,
+                file.and_then(|f| f.name.clone()),
+                file.and_then(|f| f.uid.clone()),
+                file.and_then(|f| f.gid.clone()),
+                file.and_then(|f| f.mode),
+            ))
+        })
+        .collect();
+    let configs: Vec<FileReference> = container
+        .configs
+        .iter()
+        .flatten()
+        .filter_map(|config| {
+            let source = config.config_name.clone()?;
+            let file = config.file.as_ref();
+            let target = file
+                .and_then(|f| f.name.clone())
+                .filter(|target| target.trim_start_matches('/') != source);
+            Some(file_reference(
+                source,
+                target,
+                file.and_then(|f| f.uid.clone()),
+                file.and_then(|f| f.gid.clone()),
+                file.and_then(|f| f.mode),
+            ))
+        })
+        .collect();
+
+    let dns = container.dns_config.as_ref();
+    let service = Service {
+        image,
+        // Swarm matches nodes against the platforms of the image manifest
+        platform: None,
+        container_name: None,
+        hostname: container.hostname.clone().filter(|h| !h.is_empty()),
+        environment: environment.filter(|env| !env.is_empty()),
+        ports: Some(ports).filter(|p| !p.is_empty()),
+        expose: None,
+        volumes: Some(volumes).filter(|v| !v.is_empty()),
+        tmpfs: Some(tmpfs).filter(|t| !t.is_empty()),
+        networks: service_networks,
+        network_mode: None,
+        dns: dns
+            .and_then(|d| d.nameservers.clone())
+            .filter(|d| !d.is_empty()),
+        dns_search: dns.and_then(|d| d.search.clone()).filter(|s| !s.is_empty()),
+        extra_hosts: container
+            .hosts
+            .as_ref()
+            .map(|hosts| hosts.iter().flat_map(|entry| extra_hosts(entry)).collect())
+            .filter(|hosts: &Vec<String>| !hosts.is_empty()),
+        // Swarm restarts tasks according to deploy.restart_policy instead
+        restart: None,
+        cap_add: container.capability_add.clone().filter(|c| !c.is_empty()),
+        cap_drop: container.capability_drop.clone().filter(|c| !c.is_empty()),
+        security_opt: None,
+        pid: None,
+        ipc: None,
+        uts: None,
+        userns_mode: None,
+        cgroup: None,
+        cgroup_parent: None,
+        isolation: container
+            .isolation
+            .map(|i| i.to_string())
+            .filter(|i| !i.is_empty() && i != "default"),
+        runtime: None,
+        cpu_shares: None,
+        cpu_period: None,
+        cpu_quota: None,
+        cpuset: None,
+        mem_swappiness: None,
+        memswap_limit: None,
+        pids_limit: task
+            .resources
+            .as_ref()
+            .and_then(|r| r.limits.as_ref())
+            .and_then(|limits| limits.pids)
+            .filter(|p| *p > 0),
+        oom_kill_disable: None,
+        oom_score_adj: container.oom_score_adj.filter(|a| *a != 0),
+        shm_size: None,
+        blkio_config: None,
+        deploy: deploy(spec),
+        healthcheck: container
+            .health_check
+            .as_ref()
+            .filter(|hc| hc.test.as_ref().is_some_and(|t| !t.is_empty()))
+            .map(DockerProcessor::health_check),
+        labels: container.labels.clone().and_then(filter_system_labels),
+        logging: task.log_driver.as_ref().and_then(|driver| {
+            Some(Logging {
+                driver: driver.name.clone().filter(|n| !n.is_empty())?,
+                options: driver.options.clone().filter(|o| !o.is_empty()),
+            })
+        }),
+        devices: None,
+        user: container.user.clone().filter(|u| !u.is_empty()),
+        working_dir: container.dir.clone().filter(|d| !d.is_empty()),
+        entrypoint: container.command.clone().filter(|c| !c.is_empty()),
+        command: container.args.clone().filter(|a| !a.is_empty()),
+        ulimits: container
+            .ulimits
+            .as_ref()
+            .map(|ulimits| {
+                ulimits
+                    .iter()
+                    .filter_map(|ulimit| {
+                        Some((
+                            ulimit.name.clone()?,
+                            UlimitConfig {
+                                soft: ulimit.soft?,
+                                hard: ulimit.hard?,
+                            },
+                        ))
+                    })
+                    .collect::<HashMap<_, _>>()
+            })
+            .filter(|u| !u.is_empty()),
+        sysctls: container.sysctls.clone().filter(|s| !s.is_empty()),
+        init: container.init.filter(|i| *i),
+        privileged: None,
+        tty: container.tty.filter(|t| *t),
+        stdin_open: container.open_stdin.filter(|o| *o),
+        read_only: container.read_only.filter(|r| *r),
+        stop_signal: container.stop_signal.clone().filter(|s| !s.is_empty()),
+        stop_grace_period: container
+            .stop_grace_period
+            .filter(|p| *p > 0)
+            .map(normalize_duration_from_ns),
+        // Stack files follow the version 3 schema, which has no key for these
+        group_add: None,
+        domainname: None,
+        mac_address: None,
+        storage_opt: None,
+        device_cgroup_rules: None,
+        annotations: None,
+        secrets: Some(secrets).filter(|s| !s.is_empty()),
+        configs: Some(configs).filter(|c| !c.is_empty()),
+        depends_on: None,
+        x_podman: None,
+        compose: stack.clone().map(|stack| ComposeLabels {
+            project: stack,
+            service: short_name,
+            ..Default::default()
+        }),
+    };
+
+    Some(StackService {
+        name: sanitize_service_name(&full_name),
+        stack,
+        service,
+        networks,
+        volumes: volume_names,
+    })
+}
+
+/// Swarm's `hosts` entries read `IP hostname [aliases...]`; compose wants
+/// one `hostname:IP` per name.
+fn extra_hosts(entry: &str) -> Vec<String> {
+    let mut fields = entry.split_whitespace();
+    match fields.next() {
+        Some(ip) if entry.contains(char::is_whitespace) => {
+            fields.map(|host| format!("{}:{}", host, ip)).collect()
+        }
+        Some(_) => vec![entry.to_string()],
+        None => Vec::new(),
+    }
+}
+
+fn file_reference(
+    source: String,
+    target: Option<String>,
+    uid: Option<String>,
+    gid: Option<String>,
+    mode: Option<u32>,
+) -> FileReference {
+    FileReference {
+        target: target.filter(|t| !t.is_empty() && *t != source),
+        uid: uid.filter(|u| !u.is_empty() && u != "0"),
+        gid: gid.filter(|g| !g.is_empty() && g != "0"),
+        mode: mode.filter(|m| *m != DEFAULT_FILE_MODE),
+        source,
+    }
+}
+
+fn deploy(spec: &ServiceSpec) -> Option<Deploy> {
+    let task = spec.task_template.as_ref();
+
+    let (mode, replicas) = match &spec.mode {
+        Some(ServiceSpecMode {
+            global: Some(_), ..
+        }) => (Some("global"), None),
+        Some(ServiceSpecMode {
+            replicated_job: Some(_),
+            ..
+        }) => (Some("replicated-job"), None),
+        Some(ServiceSpecMode {
+            global_job: Some(_),
+            ..
+        }) => (Some("global-job"), None),
+        Some(ServiceSpecMode {
+            replicated: Some(replicated),
+            ..
+        }) => (None, replicated.replicas.map(|r| r.max(0) as u32)),
+        _ => (None, None),
+    };
+
+    let endpoint_mode = spec
+        .endpoint_spec
+        .as_ref()
+        .and_then(|endpoint| endpoint.mode)
+        .filter(|mode| *mode == EndpointSpecModeEnum::DNSRR)
+        .map(|mode| mode.to_string());
+
+    let placement = task
+        .and_then(|t| t.placement.as_ref())
+        .and_then(|placement| {
+            let placement = Placement {
+                constraints: placement.constraints.clone().filter(|c| !c.is_empty()),
+                preferences: placement
+                    .preferences
+                    .as_ref()
+                    .map(|preferences| {
+                        preferences
+                            .iter()
+                            .filter_map(|p| p.spread.as_ref()?.spread_descriptor.clone())
+                            .map(|spread| PlacementPreference { spread })
+                            .collect::<Vec<_>>()
+                    })
+                    .filter(|p| !p.is_empty()),
+                max_replicas_per_node: placement.max_replicas.filter(|m| *m > 0).map(|m| m as u64),
+            };
+            (placement.constraints.is_some()
+                || placement.preferences.is_some()
+                || placement.max_replicas_per_node.is_some())
+            .then_some(placement)
+        });
+
+    let resources = task
+        .and_then(|t| t.resources.as_ref())
+        .and_then(|resources| {
+            let cpus = |nano_cpus: Option<i64>| {
+                nano_cpus
+                    .filter(|c| *c > 0)
+                    .map(|c| format!("{:.2}", c as f64 / 1e9))
+            };
+            let memory = |bytes: Option<i64>| bytes.filter(|m| *m > 0).map(format_size_bytes);
+            let limits = resources.limits.as_ref();
+            let reservations = resources.reservations.as_ref();
+            Resources::new(
+                ResourceLimits {
+                    cpus: cpus(limits.and_then(|l| l.nano_cpus)),
+                    memory: memory(limits.and_then(|l| l.memory_bytes)),
+                },
+                ResourceReservations {
+                    cpus: cpus(reservations.and_then(|r| r.nano_cpus)),
+                    memory: memory(reservations.and_then(|r| r.memory_bytes)),
+                    // Swarm reserves generic resources, not devices
+                    devices: None,
+                },
+            )
+        });
+
+    let update = spec.update_config.as_ref().and_then(|config| {
+        update_config(
+            config.parallelism,
+            config.delay,
+            config.failure_action.map(|a| a.to_string()),
+            config.monitor,
+            config.max_failure_ratio,
+            config.order.map(|o| o.to_string()),
+        )
+    });
+    let rollback = spec.rollback_config.as_ref().and_then(|config| {
+        update_config(
+            config.parallelism,
+            config.delay,
+            config.failure_action.map(|a| a.to_string()),
+            config.monitor,
+            config.max_failure_ratio,
+            config.order.map(|o| o.to_string()),
+        )
+    });
+
+    let deploy = Deploy {
+        mode: mode.map(str::to_string),
+        replicas,
+        endpoint_mode,
+        labels: spec.labels.clone().and_then(filter_system_labels),
+        resources,
+        restart_policy: task
+            .and_then(|t| t.restart_policy.as_ref())
+            .and_then(restart_policy),
+        placement,
+        update_config: update,
+        rollback_config: rollback,
+    };
+
+    let is_empty = deploy.mode.is_none()
+        && deploy.replicas.is_none()
+        && deploy.endpoint_mode.is_none()
+        && deploy.labels.is_none()
+        && deploy.resources.is_none()
+        && deploy.restart_policy.is_none()
+        && deploy.placement.is_none()
+        && deploy.update_config.is_none()
+        && deploy.rollback_config.is_none();
+    (!is_empty).then_some(deploy)
+}
+
+/// Leaves out the values Swarm would fill in by itself.
+fn restart_policy(policy: &TaskSpecRestartPolicy) -> Option<RestartPolicy> {
+    let policy = RestartPolicy {
+        condition: policy
+            .condition
+            .map(|c| c.to_string())
+            .filter(|c| !c.is_empty() && c != "any"),
+        delay: policy
+            .delay
+            .filter(|d| *d != DEFAULT_RESTART_DELAY_NS)
+            .map(normalize_duration_from_ns),
+        max_attempts: policy.max_attempts.filter(|m| *m > 0).map(|m| m as u64),
+        window: policy
+            .window
+            .filter(|w| *w > 0)
+            .map(normalize_duration_from_ns),
+    };
+    (policy != RestartPolicy::default()).then_some(policy)
+}
+
+/// Leaves out the values Swarm would fill in by itself.
+fn update_config(
+    parallelism: Option<i64>,
+    delay: Option<i64>,
+    failure_action: Option<String>,
+    monitor: Option<i64>,
+    max_failure_ratio: Option<f64>,
+    order: Option<String>,
+) -> Option<UpdateConfig> {
+    let config = UpdateConfig {
+        parallelism: parallelism.filter(|p| *p != 1).map(|p| p.max(0) as u64),
+        delay: delay.filter(|d| *d > 0).map(normalize_duration_from_ns),
+        failure_action: failure_action.filter(|a| !a.is_empty() && a != "pause"),
+        monitor: monitor
+            .filter(|m| *m > 0 && *m != DEFAULT_MONITOR_NS)
+            .map(normalize_duration_from_ns),
+        max_failure_ratio: max_failure_ratio.filter(|r| *r > 0.0),
+        order: order.filter(|o| !o.is_empty() && o != "stop-first"),
+    };
+    (config != UpdateConfig::default()).then_some(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> ServiceSpec {
+        serde_json::from_value(serde_json::json!({
+            "Name": "shop_api",
+            "Labels": {
+                "com.docker.stack.namespace": "shop",
+                "com.docker.stack.image": "acme/api:1.4",
+                "tier": "backend"
+            },
+            "TaskTemplate": {
+                "ContainerSpec": {
+                    "Image": "acme/api:1.4@sha256:0123456789abcdef",
+                    "Env": ["PORT=8080", "DB_PASSWORD=hunter2"],
+                    "Hosts": ["10.0.0.5 db.internal db"],
+                    "Secrets": [{
+                        "File": {"Name": "db_password", "UID": "0", "GID": "0", "Mode": 292},
+                        "SecretID": "k2x8",
+                        "SecretName": "db_password"
+                    }],
+                    "Configs": [{
+                        "File": {
+                            "Name": "/etc/api/config.yml",
+                            "UID": "0",
+                            "GID": "0",
+                            "Mode": 292
+                        },
+                        "ConfigID": "p9q1",
+                        "ConfigName": "shop_api_config"
+                    }]
+                },
+                "RestartPolicy": {
+                    "Condition": "on-failure",
+                    "Delay": 5_000_000_000i64,
+                    "MaxAttempts": 3
+                },
+                "Placement": {
+                    "Constraints": ["node.role == worker"],
+                    "Preferences": [{"Spread": {"SpreadDescriptor": "node.labels.zone"}}]
+                },
+                "Networks": [{"Target": "n1", "Aliases": ["api"]}]
+            },
+            "Mode": {"Replicated": {"Replicas": 3}},
+            "UpdateConfig": {
+                "Parallelism": 2,
+                "FailureAction": "rollback",
+                "Monitor": 5_000_000_000i64,
+                "MaxFailureRatio": 0,
+                "Order": "start-first"
+            },
+            "RollbackConfig": {
+                "Parallelism": 1,
+                "FailureAction": "pause",
+                "Monitor": 5_000_000_000i64,
+                "MaxFailureRatio": 0,
+                "Order": "stop-first"
+            },
+            "EndpointSpec": {
+                "Mode": "vip",
+                "Ports": [{"Protocol": "tcp", "TargetPort": 8080, "PublishedPort": 80}]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_stack_service_from_spec() {
+        let networks = HashMap::from([("n1".to_string(), "shop_backend".to_string())]);
+        let converted = stack_service(&spec(), &networks, false, false).unwrap();
+        let service = &converted.service;
+
+        assert_eq!(converted.name, "shop_api");
+        assert_eq!(converted.stack.as_deref(), Some("shop"));
+        assert_eq!(service.compose.as_ref().unwrap().service, "api");
+        assert_eq!(service.image, "acme/api:1.4");
+        assert!(!service
+            .environment
+            .as_ref()
+            .unwrap()
+            .contains_key("DB_PASSWORD"));
+        assert_eq!(
+            service.extra_hosts.as_ref().unwrap(),
+            &["db.internal:10.0.0.5", "db:10.0.0.5"]
+        );
+        assert_eq!(service.ports.as_ref().unwrap(), &["80:8080"]);
+        assert!(
+            matches!(&service.networks, Some(ServiceNetworks::Simple(n)) if n == &["shop_backend"])
+        );
+
+        let secrets = service.secrets.as_ref().unwrap();
+        assert_eq!(
+            secrets[0],
+            file_reference("db_password".to_string(), None, None, None, None)
+        );
+        let configs = service.configs.as_ref().unwrap();
+        assert_eq!(configs[0].target.as_deref(), Some("/etc/api/config.yml"));
+
+        let deploy = service.deploy.as_ref().unwrap();
+        assert_eq!(deploy.replicas, Some(3));
+        assert!(deploy.mode.is_none() && deploy.endpoint_mode.is_none());
+        assert_eq!(deploy.labels.as_ref().unwrap().len(), 1);
+        let restart = deploy.restart_policy.as_ref().unwrap();
+        assert_eq!(restart.condition.as_deref(), Some("on-failure"));
+        assert!(restart.delay.is_none());
+        assert_eq!(restart.max_attempts, Some(3));
+        let placement = deploy.placement.as_ref().unwrap();
+        assert_eq!(
+            placement.preferences.as_ref().unwrap()[0].spread,
+            "node.labels.zone"
+        );
+        let update = deploy.update_config.as_ref().unwrap();
+        assert_eq!(update.parallelism, Some(2));
+        assert_eq!(update.failure_action.as_deref(), Some("rollback"));
+        assert_eq!(update.order.as_deref(), Some("start-first"));
+        assert!(update.monitor.is_none());
+        assert!(deploy.rollback_config.is_none());
+    }
+
+    #[test]
+    fn test_global_mode() {
+        let mut spec = spec();
+        spec.mode = serde_json::from_value(serde_json::json!({"Global": {}})).unwrap();
+        let converted = stack_service(&spec, &HashMap::new(), true, true).unwrap();
+        let deploy = converted.service.deploy.unwrap();
+
+        assert_eq!(deploy.mode.as_deref(), Some("global"));
+        assert!(deploy.replicas.is_none());
+        assert_eq!(
+            converted.service.image,
+            "acme/api:1.4@sha256:0123456789abcdef"
+        );
+    }
+}
@@ -8,11 +8,17 @@ Commercial use, redistribution, or reuse in other software is prohibited without
 Contact: contact@olympus-chain.fr
 */
 
-use crate::{AutoComposeError, ComposeFile, Result, Service};
+use crate::{
+    is_pinned_image, AutoComposeError, ComposeFile, DependsOn, Result, Service, ServiceNetworks,
+    ServiceVolume, VolumeMount,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
+/// Target version standing for the unversioned Compose Specification.
+pub const LATEST_SPEC: &str = "latest";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationReport {
     pub is_valid: bool,
@@ -22,6 +28,18 @@ pub struct ValidationReport {
     pub summary: ValidationSummary,
 }
 
+impl ValidationReport {
+    /// True when strict mode should reject the file: any warning, or any
+    /// suggestion that is more than informational.
+    pub fn fails_strict(&self) -> bool {
+        !self.warnings.is_empty()
+            || self
+                .suggestions
+                .iter()
+                .any(|s| !matches!(s.suggestion_type, SuggestionType::Informational))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationError {
     pub service: Option<String>,
@@ -51,7 +69,8 @@ pub struct ValidationSummary {
     pub total_services: usize,
     pub total_networks: usize,
     pub total_volumes: usize,
-    pub compose_version: String,
+    /// `None` for a Compose Specification file without `version`.
+    pub compose_version: Option<String>,
     pub validation_time_ms: u128,
 }
 
@@ -81,6 +100,8 @@ pub enum SuggestionType {
     BestPractice,
     Modernization,
     Cleanup,
+    /// Worth knowing, but never a reason to fail strict validation
+    Informational,
 }
 
 pub struct Validator {
@@ -181,7 +202,24 @@ impl Validator {
         errors: &mut Vec<ValidationError>,
         warnings: &mut Vec<ValidationWarning>,
     ) {
-        let version = &compose_file.version;
+        let target = self.target_version.as_deref();
+
+        // The Compose Specification has no version; everything goes
+        let Some(version) = &compose_file.version else {
+            if let Some(target) = target.filter(|t| *t != LATEST_SPEC) {
+                warnings.push(ValidationWarning {
+                    service: None,
+                    field: Some("version".to_string()),
+                    message: format!(
+                        "File has no version and follows the Compose Specification, \
+                         target version is {}",
+                        target
+                    ),
+                    warning_type: WarningType::PortabilityIssue,
+                });
+            }
+            return;
+        };
 
         if !self.is_valid_compose_version(version) {
             errors.push(ValidationError {
@@ -193,15 +231,20 @@ impl Validator {
             return;
         }
 
-        if let Some(target) = &self.target_version {
-            if version != target {
-                warnings.push(ValidationWarning {
-                    service: None,
-                    field: Some("version".to_string()),
-                    message: format!("Version {} differs from target version {}", version, target),
-                    warning_type: WarningType::PortabilityIssue,
-                });
-            }
+        match target {
+            Some(LATEST_SPEC) => warnings.push(ValidationWarning {
+                service: None,
+                field: Some("version".to_string()),
+                message: "`version` is obsolete in the Compose Specification".to_string(),
+                warning_type: WarningType::DeprecatedFeature,
+            }),
+            Some(target) if version != target => warnings.push(ValidationWarning {
+                service: None,
+                field: Some("version".to_string()),
+                message: format!("Version {} differs from target version {}", version, target),
+                warning_type: WarningType::PortabilityIssue,
+            }),
+            _ => {}
         }
 
         if self.is_deprecated_version(version) {
@@ -212,6 +255,100 @@ impl Validator {
                 warning_type: WarningType::DeprecatedFeature,
             });
         }
+
+        for (service, field) in Self::specification_fields(compose_file) {
+            warnings.push(ValidationWarning {
+                field: Some(field.to_string()),
+                message: format!(
+                    "{} is not part of compose file format {}, leave out `version` to use it",
+                    field, version
+                ),
+                service,
+                warning_type: WarningType::PortabilityIssue,
+            });
+        }
+    }
+
+    /// Fields only the Compose Specification knows, which 3.x tools reject.
+    fn specification_fields(compose_file: &ComposeFile) -> Vec<(Option<String>, &'static str)> {
+        let mut fields = Vec::new();
+        if compose_file.name.is_some() {
+            fields.push((None, "name"));
+        }
+
+        let mut services: Vec<_> = compose_file.services.iter().collect();
+        services.sort_by_key(|(name, _)| name.as_str());
+        for (name, service) in services {
+            let mut found = Vec::new();
+            if matches!(service.depends_on, Some(DependsOn::Extended(_))) {
+                found.push("depends_on.condition");
+            }
+            if service.annotations.is_some() {
+                found.push("annotations");
+            }
+            // Keys of the 2.x formats or the Compose Specification that 3.x lacks
+            for (field, present) in [
+                ("cpu_shares", service.cpu_shares.is_some()),
+                ("cpu_quota", service.cpu_quota.is_some()),
+                ("cpu_period", service.cpu_period.is_some()),
+                ("cpuset", service.cpuset.is_some()),
+                ("memswap_limit", service.memswap_limit.is_some()),
+                ("mem_swappiness", service.mem_swappiness.is_some()),
+                ("pids_limit", service.pids_limit.is_some()),
+                ("oom_kill_disable", service.oom_kill_disable.is_some()),
+                ("oom_score_adj", service.oom_score_adj.is_some()),
+                ("blkio_config", service.blkio_config.is_some()),
+                ("group_add", service.group_add.is_some()),
+                ("platform", service.platform.is_some()),
+                ("uts", service.uts.is_some()),
+                ("runtime", service.runtime.is_some()),
+                ("cgroup", service.cgroup.is_some()),
+                ("storage_opt", service.storage_opt.is_some()),
+                ("device_cgroup_rules", service.device_cgroup_rules.is_some()),
+            ] {
+                if present {
+                    found.push(field);
+                }
+            }
+            let device_requests = service
+                .deploy
+                .as_ref()
+                .and_then(|d| d.resources.as_ref())
+                .and_then(|r| r.reservations.as_ref())
+                .is_some_and(|r| r.devices.is_some());
+            if device_requests {
+                found.push("deploy.resources.reservations.devices");
+            }
+            if let Some(ServiceNetworks::Advanced(endpoints)) = &service.networks {
+                for endpoint in endpoints.values() {
+                    for (field, present) in [
+                        ("networks.gw_priority", endpoint.gw_priority.is_some()),
+                        ("networks.link_local_ips", endpoint.link_local_ips.is_some()),
+                        ("networks.mac_address", endpoint.mac_address.is_some()),
+                        ("networks.driver_opts", endpoint.driver_opts.is_some()),
+                    ] {
+                        if present {
+                            found.push(field);
+                        }
+                    }
+                }
+            }
+            for volume in service.volumes.iter().flatten() {
+                let ServiceVolume::Long(mount) = volume else {
+                    continue;
+                };
+                if mount.volume.as_ref().is_some_and(|v| v.subpath.is_some()) {
+                    found.push("volumes.volume.subpath");
+                }
+                if mount.bind.as_ref().is_some_and(|b| b.create_host_path.is_some()) {
+                    found.push("volumes.bind.create_host_path");
+                }
+            }
+            found.sort_unstable();
+            found.dedup();
+            fields.extend(found.into_iter().map(|field| (Some(name.clone()), field)));
+        }
+        fields
     }
 
     fn validate_services(
@@ -252,6 +389,17 @@ impl Validator {
             });
         }
 
+        if !service.image.is_empty() && !is_pinned_image(&service.image) {
+            suggestions.push(ValidationSuggestion {
+                service: Some(service_name.to_string()),
+                field: Some("image".to_string()),
+                message:
+                    "Pin the image to a digest (image:tag@sha256:...) for reproducible deployments"
+                        .to_string(),
+                suggestion_type: SuggestionType::Informational,
+            });
+        }
+
         if let Some(network_mode) = &service.network_mode {
             if network_mode == "host" {
                 warnings.push(ValidationWarning {
@@ -263,6 +411,26 @@ impl Validator {
             }
         }
 
+        for (field, namespace, mode) in [
+            ("pid", "process", &service.pid),
+            ("ipc", "IPC", &service.ipc),
+            ("uts", "UTS", &service.uts),
+            ("userns_mode", "user", &service.userns_mode),
+            ("cgroup", "cgroup", &service.cgroup),
+        ] {
+            if mode.as_deref() == Some("host") {
+                warnings.push(ValidationWarning {
+                    service: Some(service_name.to_string()),
+                    field: Some(field.to_string()),
+                    message: format!(
+                        "Sharing the host {} namespace reduces container isolation",
+                        namespace
+                    ),
+                    warning_type: WarningType::SecurityRisk,
+                });
+            }
+        }
+
         if let Some(cap_add) = &service.cap_add {
             if cap_add.contains(&"SYS_ADMIN".to_string()) || cap_add.contains(&"ALL".to_string()) {
                 warnings.push(ValidationWarning {
@@ -308,16 +476,25 @@ impl Validator {
 
         if let Some(volumes) = &service.volumes {
             for volume in volumes {
-                if volume.contains(":/") && !volume.contains(":ro") {
-                    suggestions.push(ValidationSuggestion {
-                        service: Some(service_name.to_string()),
-                        field: Some("volumes".to_string()),
-                        message: "Consider making bind mounts read-only when possible".to_string(),
-                        suggestion_type: SuggestionType::SecurityImprovement,
-                    });
-                    break;
+                if let ServiceVolume::Long(mount) = volume {
+                    self.validate_volume_mount(service_name, mount, errors);
                 }
             }
+
+            let has_writable_bind = volumes.iter().any(|volume| match volume {
+                ServiceVolume::Short(spec) => spec.contains(":/") && !spec.contains(":ro"),
+                ServiceVolume::Long(mount) => {
+                    mount.mount_type == "bind" && mount.read_only != Some(true)
+                }
+            });
+            if has_writable_bind {
+                suggestions.push(ValidationSuggestion {
+                    service: Some(service_name.to_string()),
+                    field: Some("volumes".to_string()),
+                    message: "Consider making bind mounts read-only when possible".to_string(),
+                    suggestion_type: SuggestionType::SecurityImprovement,
+                });
+            }
         }
 
         if let Some(deploy) = &service.deploy {
@@ -338,6 +515,114 @@ impl Validator {
         }
     }
 
+    fn validate_volume_mount(
+        &self,
+        service_name: &str,
+        mount: &VolumeMount,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let mut push_error = |message: String, error_type: ErrorType| {
+            errors.push(ValidationError {
+                service: Some(service_name.to_string()),
+                field: Some("volumes".to_string()),
+                message,
+                error_type,
+            });
+        };
+
+        if !matches!(
+            mount.mount_type.as_str(),
+            "bind" | "volume" | "tmpfs" | "npipe" | "cluster" | "image"
+        ) {
+            push_error(
+                format!("Unknown volume type: {}", mount.mount_type),
+                ErrorType::InvalidFieldValue,
+            );
+            return;
+        }
+
+        if mount.target.is_empty() {
+            push_error(
+                "Volume target must not be empty".to_string(),
+                ErrorType::MissingRequiredField,
+            );
+        } else if !mount.target.starts_with('/') && mount.mount_type != "npipe" {
+            push_error(
+                format!("Volume target must be an absolute path: {}", mount.target),
+                ErrorType::InvalidFieldValue,
+            );
+        }
+
+        let has_source = mount.source.as_ref().is_some_and(|s| !s.is_empty());
+        if mount.mount_type == "bind" && !has_source {
+            push_error(
+                format!("Bind mount for {} requires a source", mount.target),
+                ErrorType::MissingRequiredField,
+            );
+        }
+        if mount.mount_type == "tmpfs" && has_source {
+            push_error(
+                format!("tmpfs mount for {} cannot have a source", mount.target),
+                ErrorType::ConflictingConfiguration,
+            );
+        }
+
+        for (options_type, present) in [
+            ("bind", mount.bind.is_some()),
+            ("volume", mount.volume.is_some()),
+            ("tmpfs", mount.tmpfs.is_some()),
+        ] {
+            if present && mount.mount_type != options_type {
+                push_error(
+                    format!(
+                        "'{}' options are not allowed on a {} mount",
+                        options_type, mount.mount_type
+                    ),
+                    ErrorType::ConflictingConfiguration,
+                );
+            }
+        }
+
+        if let Some(propagation) = mount.bind.as_ref().and_then(|b| b.propagation.as_deref()) {
+            if !matches!(
+                propagation,
+                "private" | "rprivate" | "shared" | "rshared" | "slave" | "rslave"
+            ) {
+                push_error(
+                    format!("Invalid bind propagation: {}", propagation),
+                    ErrorType::InvalidFieldValue,
+                );
+            }
+        }
+
+        if let Some(selinux) = mount.bind.as_ref().and_then(|b| b.selinux.as_deref()) {
+            if selinux != "z" && selinux != "Z" {
+                push_error(
+                    format!("Invalid SELinux relabel option: {}", selinux),
+                    ErrorType::InvalidFieldValue,
+                );
+            }
+        }
+
+        if let Some(mode) = mount.tmpfs.as_ref().and_then(|t| t.mode) {
+            if mode.to_string().chars().any(|c| c > '7') {
+                push_error(
+                    format!("tmpfs mode must be written in octal, got {}", mode),
+                    ErrorType::InvalidFieldValue,
+                );
+            }
+        }
+
+        if let Some(size) = mount.tmpfs.as_ref().and_then(|t| t.size) {
+            if size <= 0 {
+                push_error(
+                    format!("tmpfs size must be positive, got {}", size),
+                    ErrorType::InvalidFieldValue,
+                );
+            }
+        }
+    }
+
     fn check_best_practices_compliance(
         &self,
         compose_file: &ComposeFile,
@@ -419,7 +704,14 @@ fn format_text_report(report: &ValidationReport) -> String {
     output.push_str(&format!("Services: {}\n", report.summary.total_services));
     output.push_str(&format!("Networks: {}\n", report.summary.total_networks));
     output.push_str(&format!("Volumes: {}\n", report.summary.total_volumes));
-    output.push_str(&format!("Version: {}\n", report.summary.compose_version));
+    output.push_str(&format!(
+        "Version: {}\n",
+        report
+            .summary
+            .compose_version
+            .as_deref()
+            .unwrap_or("none (Compose Specification)")
+    ));
     output.push_str(&format!(
         "Validation time: {}ms\n\n",
         report.summary.validation_time_ms
@@ -503,48 +795,207 @@ mod tests {
         let service = Service {
             image: "nginx:latest".to_string(),
             container_name: Some("test".to_string()),
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         services.insert("test".to_string(), service);
 
         let compose_file = ComposeFile {
-            version: "3.9".to_string(),
+            version: Some("3.9".to_string()),
+            name: None,
             services,
             networks: None,
             volumes: None,
+            secrets: None,
+            configs: None,
         };
 
         let report = validator.validate_compose_object(&compose_file);
         assert!(report.is_valid);
         assert!(!report.warnings.is_empty()); // Should warn about 'latest' tag
     }
+
+    #[test]
+    fn test_unpinned_image_suggestion() {
+        let validator = Validator::new(true, None);
+        let mut services = HashMap::new();
+        for (name, image) in [("web", "nginx:1.25"), ("db", "postgres:16@sha256:abcd")] {
+            services.insert(
+                name.to_string(),
+                serde_yaml::from_str::<Service>(&format!("image: \"{}\"", image)).unwrap(),
+            );
+        }
+        let compose = ComposeFile {
+            version: Some("3.9".to_string()),
+            name: None,
+            services,
+            networks: None,
+            volumes: None,
+            secrets: None,
+            configs: None,
+        };
+
+        let report = validator.validate_compose_object(&compose);
+        let pin_suggestions: Vec<_> = report
+            .suggestions
+            .iter()
+            .filter(|s| s.message.contains("digest"))
+            .collect();
+        assert_eq!(pin_suggestions.len(), 1);
+        assert_eq!(pin_suggestions[0].service.as_deref(), Some("web"));
+        // Unpinned images alone must not fail --strict
+        assert!(matches!(
+            pin_suggestions[0].suggestion_type,
+            SuggestionType::Informational
+        ));
+    }
+
+    #[test]
+    fn test_unversioned_compose_specification() {
+        let yaml = r#"
+name: shop
+services:
+  web:
+    image: nginx:1.25
+    depends_on:
+      db:
+        condition: service_healthy
+  db:
+    image: postgres:16
+    platform: linux/amd64
+    cpu_shares: 512
+    pids_limit: 100
+"#;
+        let mut compose: ComposeFile = serde_yaml::from_str(yaml).unwrap();
+        assert!(compose.version.is_none());
+
+        let report = Validator::new(false, Some(LATEST_SPEC.to_string()))
+            .validate_compose_object(&compose);
+        assert!(report.is_valid);
+        assert!(report.warnings.is_empty());
+        assert!(serde_yaml::to_string(&compose).unwrap().starts_with("name: shop\n"));
+
+        // The same file pinned to 3.9 uses keys that format does not have
+        compose.version = Some("3.9".to_string());
+        let report = Validator::new(false, None).validate_compose_object(&compose);
+        assert!(report.is_valid);
+        let fields: Vec<_> = report.warnings.iter().filter_map(|w| w.field.as_deref()).collect();
+        assert_eq!(
+            fields,
+            [
+                "name",
+                "cpu_shares",
+                "pids_limit",
+                "platform",
+                "depends_on.condition"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fields_missing_from_compose_3() {
+        let yaml = r#"
+version: "3.9"
+services:
+  app:
+    image: acme/app:1.0
+    uts: host
+    runtime: runsc
+    cgroup: private
+    storage_opt:
+      size: 20G
+    device_cgroup_rules: ["c 42:* rmw"]
+    networks:
+      backend:
+        link_local_ips: [169.254.10.5]
+        mac_address: 02:42:ac:11:00:02
+        driver_opts:
+          com.example.mtu: "1400"
+"#;
+        let compose: ComposeFile = serde_yaml::from_str(yaml).unwrap();
+
+        let fields: Vec<_> = Validator::specification_fields(&compose)
+            .into_iter()
+            .map(|(_, field)| field)
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "cgroup",
+                "device_cgroup_rules",
+                "networks.driver_opts",
+                "networks.link_local_ips",
+                "networks.mac_address",
+                "runtime",
+                "storage_opt",
+                "uts"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_host_namespace_warnings() {
+        let validator = Validator::new(false, None);
+        let service: Service = serde_yaml::from_str(
+            "image: node-exporter:1.8\npid: host\nipc: shareable\nuts: host\nruntime: runsc",
+        )
+        .unwrap();
+
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        let mut suggestions = Vec::new();
+        validator.validate_service(
+            "exporter",
+            &service,
+            &mut errors,
+            &mut warnings,
+            &mut suggestions,
+        );
+
+        let fields: Vec<_> = warnings
+            .iter()
+            .filter(|w| matches!(w.warning_type, WarningType::SecurityRisk))
+            .filter_map(|w| w.field.as_deref())
+            .collect();
+        assert_eq!(fields, ["pid", "uts"]);
+    }
+
+    #[test]
+    fn test_long_volume_validation() {
+        let validator = Validator::new(false, None);
+        let mount = |mount_type: &str, source: Option<&str>, target: &str| VolumeMount {
+            mount_type: mount_type.to_string(),
+            source: source.map(|s| s.to_string()),
+            target: target.to_string(),
+            read_only: None,
+            bind: None,
+            volume: None,
+            tmpfs: None,
+        };
+
+        let mut errors = Vec::new();
+        validator.validate_volume_mount("web", &mount("bind", Some("/srv"), "/srv"), &mut errors);
+        validator.validate_volume_mount("web", &mount("volume", None, "/cache"), &mut errors);
+        assert!(errors.is_empty());
+
+        validator.validate_volume_mount("web", &mount("bind", None, "/srv"), &mut errors);
+        validator.validate_volume_mount("web", &mount("tmpfs", Some("x"), "/run"), &mut errors);
+        validator.validate_volume_mount("web", &mount("volume", Some("data"), "data"), &mut errors);
+        validator.validate_volume_mount("web", &mount("overlay", None, "/x"), &mut errors);
+        assert_eq!(errors.len(), 4);
+
+        let mut tmpfs_with_bind_options = mount("tmpfs", None, "/run");
+        tmpfs_with_bind_options.bind = Some(crate::BindOptions {
+            propagation: Some("rshared".to_string()),
+            create_host_path: None,
+            selinux: None,
+        });
+        tmpfs_with_bind_options.tmpfs = Some(crate::TmpfsOptions {
+            size: Some(1024),
+            mode: Some(1779),
+        });
+        let mut errors = Vec::new();
+        validator.validate_volume_mount("web", &tmpfs_with_bind_options, &mut errors);
+        assert_eq!(errors.len(), 2);
+    }
 }
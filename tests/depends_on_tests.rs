@@ -10,42 +10,17 @@ Contact: contact@olympus-chain.fr
 
 #[cfg(test)]
 mod depends_on_tests {
-    use autocompose::{ComposeFile, Service};
+    use autocompose::{
+        ComposeFile, DependencyCondition, DependencyConfig, DependsOn, Service,
+    };
     use std::collections::HashMap;
 
     fn create_service_with_dependencies(deps: Vec<String>) -> Service {
         Service {
             image: "test:latest".to_string(),
             container_name: Some("test-service".to_string()),
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: if deps.is_empty() { None } else { Some(deps) },
+            depends_on: if deps.is_empty() { None } else { Some(DependsOn::Simple(deps)) },
+            ..Default::default()
         }
     }
 
@@ -64,7 +39,7 @@ mod depends_on_tests {
         let service = create_service_with_dependencies(vec!["database".to_string()]);
         assert!(service.depends_on.is_some());
         assert_eq!(service.depends_on.as_ref().unwrap().len(), 1);
-        assert_eq!(service.depends_on.as_ref().unwrap()[0], "database");
+        assert_eq!(service.depends_on.as_ref().unwrap().services()[0], "database");
         
         // Test serialization
         let yaml = serde_yaml::to_string(&service).unwrap();
@@ -84,9 +59,9 @@ mod depends_on_tests {
         assert!(service.depends_on.is_some());
         let service_deps = service.depends_on.as_ref().unwrap();
         assert_eq!(service_deps.len(), 3);
-        assert!(service_deps.contains(&"database".to_string()));
-        assert!(service_deps.contains(&"cache".to_string()));
-        assert!(service_deps.contains(&"message-queue".to_string()));
+        assert!(service_deps.contains("database"));
+        assert!(service_deps.contains("cache"));
+        assert!(service_deps.contains("message-queue"));
         
         // Test serialization
         let yaml = serde_yaml::to_string(&service).unwrap();
@@ -128,10 +103,13 @@ mod depends_on_tests {
         );
         
         let compose = ComposeFile {
-            version: "3.9".to_string(),
+            version: Some("3.9".to_string()),
+            name: None,
             services,
             networks: None,
             volumes: None,
+            secrets: None,
+            configs: None,
         };
         
         // Test serialization
@@ -204,10 +182,13 @@ mod depends_on_tests {
         );
         
         let compose = ComposeFile {
-            version: "3.9".to_string(),
+            version: Some("3.9".to_string()),
+            name: None,
             services,
             networks: None,
             volumes: None,
+            secrets: None,
+            configs: None,
         };
         
         // Should serialize without errors (validation is runtime concern)
@@ -227,8 +208,8 @@ mod depends_on_tests {
         assert!(service.depends_on.is_some());
         let deps = service.depends_on.as_ref().unwrap();
         assert_eq!(deps.len(), 2);
-        assert!(deps.contains(&"nonexistent-service".to_string()));
-        assert!(deps.contains(&"another-missing-service".to_string()));
+        assert!(deps.contains("nonexistent-service"));
+        assert!(deps.contains("another-missing-service"));
     }
 
     #[test]
@@ -245,8 +226,8 @@ depends_on:
         assert!(service.depends_on.is_some());
         let deps = service.depends_on.unwrap();
         assert_eq!(deps.len(), 2);
-        assert!(deps.contains(&"database".to_string()));
-        assert!(deps.contains(&"cache".to_string()));
+        assert!(deps.contains("database"));
+        assert!(deps.contains("cache"));
     }
 
     #[test]
@@ -296,10 +277,13 @@ depends_on:
         );
         
         let compose = ComposeFile {
-            version: "3.9".to_string(),
+            version: Some("3.9".to_string()),
+            name: None,
             services,
             networks: None,
             volumes: None,
+            secrets: None,
+            configs: None,
         };
         
         // Serialize and verify
@@ -315,4 +299,45 @@ depends_on:
         assert!(api.depends_on.is_some());
         assert_eq!(api.depends_on.as_ref().unwrap().len(), 3);
     }
+
+    #[test]
+    fn test_long_form_dependencies() {
+        let mut conditions = HashMap::new();
+        conditions.insert(
+            "migrate".to_string(),
+            DependencyConfig {
+                condition: DependencyCondition::ServiceCompletedSuccessfully,
+                restart: None,
+                required: None,
+            },
+        );
+        conditions.insert(
+            "database".to_string(),
+            DependencyConfig {
+                condition: DependencyCondition::ServiceHealthy,
+                restart: Some(true),
+                required: Some(false),
+            },
+        );
+
+        let mut service = create_service_with_dependencies(vec![]);
+        service.depends_on = Some(DependsOn::Extended(conditions));
+
+        let deps = service.depends_on.as_ref().unwrap();
+        assert_eq!(deps.services(), vec!["database", "migrate"]);
+        assert!(deps.contains("migrate"));
+
+        let yaml = serde_yaml::to_string(&service).unwrap();
+        assert!(yaml.contains("condition: service_completed_successfully"));
+        assert!(yaml.contains("condition: service_healthy"));
+        assert!(yaml.contains("restart: true"));
+        assert!(yaml.contains("required: false"));
+
+        // Both syntaxes read back from an existing compose file
+        let parsed: Service = serde_yaml::from_str(&yaml).unwrap();
+        assert!(matches!(parsed.depends_on, Some(DependsOn::Extended(ref d)) if d.len() == 2));
+        let short: DependsOn = serde_yaml::from_str("[database, cache]").unwrap();
+        assert!(matches!(short, DependsOn::Simple(ref d) if d.len() == 2));
+    }
+
 }
\ No newline at end of file
@@ -12,6 +12,7 @@ Contact: contact@olympus-chain.fr
 mod docker_tests {
     use autocompose::{
         Deploy, HealthCheck, NetworkConfig, ResourceLimits, Resources, Service, ServiceNetworks,
+        ServiceVolume, TmpfsOptions, VolumeMount,
     };
     use std::collections::HashMap;
 
@@ -26,32 +27,15 @@ mod docker_tests {
             hostname: Some("test-host".to_string()),
             environment: Some(env),
             ports: Some(vec!["8080:80".to_string()]),
-            volumes: Some(vec!["/data:/data".to_string()]),
-            networks: None,
+            volumes: Some(vec![ServiceVolume::Short("/data:/data".to_string())]),
             network_mode: Some("bridge".to_string()),
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
             restart: Some("unless-stopped".to_string()),
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
             working_dir: Some("/app".to_string()),
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
             init: Some(false),
             privileged: Some(false),
             tty: Some(false),
             stdin_open: Some(false),
-            depends_on: None,
+            ..Default::default()
         }
     }
 
@@ -93,6 +77,7 @@ mod docker_tests {
             NetworkConfig {
                 ipv4_address: Some("172.20.0.2".to_string()),
                 ipv6_address: None,
+                ..Default::default()
             },
         );
 
@@ -105,6 +90,39 @@ mod docker_tests {
         assert!(!yaml.contains("ipv6_address:"));
     }
 
+    #[test]
+    fn test_long_volume_serialization() {
+        let mut service = create_test_service();
+        service.volumes = Some(vec![
+            ServiceVolume::Short("/data:/data".to_string()),
+            ServiceVolume::Long(VolumeMount {
+                mount_type: "tmpfs".to_string(),
+                source: None,
+                target: "/run".to_string(),
+                read_only: None,
+                bind: None,
+                volume: None,
+                tmpfs: Some(TmpfsOptions {
+                    size: Some(67108864),
+                    mode: Some(1777),
+                }),
+            }),
+        ]);
+
+        let yaml = serde_yaml::to_string(&service).unwrap();
+        assert!(yaml.contains("- /data:/data"));
+        assert!(yaml.contains("type: tmpfs"));
+        assert!(yaml.contains("target: /run"));
+        assert!(yaml.contains("size: 67108864"));
+        assert!(yaml.contains("mode: 1777"));
+        assert!(!yaml.contains("source:"));
+
+        let parsed: Service = serde_yaml::from_str(&yaml).unwrap();
+        let volumes = parsed.volumes.unwrap();
+        assert!(matches!(volumes[0], ServiceVolume::Short(_)));
+        assert!(matches!(volumes[1], ServiceVolume::Long(_)));
+    }
+
     #[test]
     fn test_healthcheck_serialization() {
         let mut service = create_test_service();
@@ -138,8 +156,9 @@ mod docker_tests {
                     cpus: Some("0.5".to_string()),
                     memory: Some("512M".to_string()),
                 }),
+                ..Default::default()
             }),
-            placement: None,
+            ..Default::default()
         });
 
         let yaml = serde_yaml::to_string(&service).unwrap();
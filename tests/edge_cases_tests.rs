@@ -13,17 +13,20 @@ mod edge_cases_tests {
     use autocompose::{
         filter_system_labels, sanitize_service_name,
         security::{filter_sensitive_env_vars, validate_container_id},
-        ComposeFile, NetworkConfig, Service, ServiceNetworks,
+        ComposeFile, DependsOn, NetworkConfig, Service, ServiceNetworks,
     };
     use std::collections::HashMap;
 
     #[test]
     fn test_empty_compose_file() {
         let compose = ComposeFile {
-            version: "3.9".to_string(),
+            version: Some("3.9".to_string()),
+            name: None,
             services: HashMap::new(),
             networks: None,
             volumes: None,
+            secrets: None,
+            configs: None,
         };
 
         let yaml = serde_yaml::to_string(&compose).unwrap();
@@ -37,36 +40,7 @@ mod edge_cases_tests {
     fn test_service_with_all_fields_none() {
         let service = Service {
             image: "test:latest".to_string(),
-            container_name: None,
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&service).unwrap();
@@ -125,35 +99,7 @@ mod edge_cases_tests {
         let service = Service {
             image: "test:latest".to_string(),
             environment: Some(env),
-            container_name: None,
-            hostname: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&service).unwrap();
@@ -173,6 +119,7 @@ mod edge_cases_tests {
             NetworkConfig {
                 ipv4_address: Some("172.20.0.2".to_string()),
                 ipv6_address: None,
+                ..Default::default()
             },
         );
 
@@ -180,43 +127,19 @@ mod edge_cases_tests {
             image: "app:latest".to_string(),
             networks: Some(ServiceNetworks::Advanced(networks)),
             container_name: Some("app".to_string()),
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         services.insert("app".to_string(), service);
 
         let compose = ComposeFile {
-            version: "3.9".to_string(),
+            version: Some("3.9".to_string()),
+            name: None,
             services,
             networks: Some(HashMap::new()),
             volumes: None,
+            secrets: None,
+            configs: None,
         };
 
         // Should serialize without issues
@@ -386,40 +309,12 @@ mod edge_cases_tests {
         let service_no_env = Service {
             image: "test:latest".to_string(),
             container_name: Some("test".to_string()),
-            environment: None,
-            hostname: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         // Processing options should work with services without environment
-        let options_sensitive = ProcessingOptions { include_sensitive: true };
-        let options_no_sensitive = ProcessingOptions { include_sensitive: false };
+        let options_sensitive = ProcessingOptions { include_sensitive: true, ..Default::default() };
+        let options_no_sensitive = ProcessingOptions { include_sensitive: false, ..Default::default() };
         
         assert!(service_no_env.environment.is_none());
         assert!(options_sensitive.include_sensitive);
@@ -432,35 +327,8 @@ mod edge_cases_tests {
         let service_empty_deps = Service {
             image: "test:latest".to_string(),
             container_name: Some("test".to_string()),
-            depends_on: Some(vec![]),
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
+            depends_on: Some(DependsOn::Simple(vec![])),
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&service_empty_deps).unwrap();
@@ -476,35 +344,8 @@ mod edge_cases_tests {
         let service_long_deps = Service {
             image: "test:latest".to_string(),
             container_name: Some("test".to_string()),
-            depends_on: Some(long_deps),
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
+            depends_on: Some(DependsOn::Simple(long_deps)),
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&service_long_deps).unwrap();
@@ -27,40 +27,23 @@ mod integration_tests {
             environment: None, // Empty map should be omitted
             ports: Some(vec!["80:80".to_string()]),
             volumes: None, // Should be omitted
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
             restart: Some("unless-stopped".to_string()),
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
             user: None,        // Should be omitted (was previously "")
             working_dir: None, // Should be omitted (was previously "")
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
             privileged: Some(false),
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         services.insert("nginx".to_string(), service);
 
         let compose_file = ComposeFile {
-            version: "3.9".to_string(),
+            version: Some("3.9".to_string()),
+            name: None,
             services,
             networks: None,
             volumes: None,
+            secrets: None,
+            configs: None,
         };
 
         // Serialize to YAML
@@ -99,43 +82,19 @@ mod integration_tests {
             image: "myapp:latest".to_string(),
             container_name: Some("myapp".to_string()),
             environment: Some(filtered_env),
-            hostname: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         services.insert("myapp".to_string(), service);
 
         let compose_file = ComposeFile {
-            version: "3.9".to_string(),
+            version: Some("3.9".to_string()),
+            name: None,
             services,
             networks: None,
             volumes: None,
+            secrets: None,
+            configs: None,
         };
 
         let yaml = serde_yaml::to_string(&compose_file).unwrap();
@@ -10,7 +10,7 @@ Contact: contact@olympus-chain.fr
 
 #[cfg(test)]
 mod processing_options_tests {
-    use autocompose::{ProcessingOptions, Service};
+    use autocompose::{cli::ExternalMode, ProcessingOptions, Service};
     use std::collections::HashMap;
 
     fn create_service_with_sensitive_env() -> Service {
@@ -25,35 +25,8 @@ mod processing_options_tests {
         Service {
             image: "test:latest".to_string(),
             container_name: Some("test-container".to_string()),
-            hostname: None,
             environment: Some(env),
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         }
     }
 
@@ -61,14 +34,17 @@ mod processing_options_tests {
     fn test_processing_options_default() {
         let options = ProcessingOptions {
             include_sensitive: false,
+            ..Default::default()
         };
         assert!(!options.include_sensitive);
+        assert_eq!(options.external, ExternalMode::Auto);
     }
 
     #[test]
     fn test_processing_options_include_sensitive() {
         let options = ProcessingOptions {
             include_sensitive: true,
+            ..Default::default()
         };
         assert!(options.include_sensitive);
     }
@@ -178,35 +154,7 @@ mod processing_options_tests {
         let service = Service {
             image: "test:latest".to_string(),
             container_name: Some("test-container".to_string()),
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         // Service with no environment should work with both options
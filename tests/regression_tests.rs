@@ -12,7 +12,7 @@ Contact: contact@olympus-chain.fr
 mod regression_tests {
     use autocompose::{
         security::{validate_container_id, validate_image_id, validate_output_path},
-        Service,
+        DependsOn, Service,
     };
     use std::collections::HashMap;
     use std::path::Path;
@@ -24,34 +24,7 @@ mod regression_tests {
             image: "nginx:latest".to_string(),
             user: None, // This used to be Some("") which created invalid YAML
             container_name: Some("test".to_string()),
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&service).unwrap();
@@ -140,8 +113,9 @@ mod regression_tests {
                     "node.role == worker".to_string(),
                     // Empty constraints should be filtered out
                 ]),
+                ..Default::default()
             }),
-            resources: None,
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&deploy).unwrap();
@@ -159,31 +133,7 @@ mod regression_tests {
             tty: Some(true),
             stdin_open: Some(false),
             container_name: Some("test".to_string()),
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            depends_on: None,
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&service).unwrap();
@@ -252,6 +202,7 @@ mod regression_tests {
         // The default behavior should exclude sensitive variables
         let default_options = ProcessingOptions {
             include_sensitive: false,
+            ..Default::default()
         };
         assert!(!default_options.include_sensitive);
 
@@ -289,35 +240,8 @@ mod regression_tests {
         let service_with_deps = Service {
             image: "app:latest".to_string(),
             container_name: Some("app".to_string()),
-            depends_on: Some(vec!["db".to_string(), "cache".to_string()]),
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
+            depends_on: Some(DependsOn::Simple(vec!["db".to_string(), "cache".to_string()])),
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&service_with_deps).unwrap();
@@ -329,35 +253,7 @@ mod regression_tests {
         let service_no_deps = Service {
             image: "app:latest".to_string(),
             container_name: Some("app".to_string()),
-            depends_on: None,
-            hostname: None,
-            environment: None,
-            ports: None,
-            volumes: None,
-            networks: None,
-            network_mode: None,
-            dns: None,
-            dns_search: None,
-            extra_hosts: None,
-            restart: None,
-            cap_add: None,
-            cap_drop: None,
-            security_opt: None,
-            deploy: None,
-            healthcheck: None,
-            labels: None,
-            logging: None,
-            devices: None,
-            user: None,
-            working_dir: None,
-            entrypoint: None,
-            command: None,
-            ulimits: None,
-            sysctls: None,
-            init: None,
-            privileged: None,
-            tty: None,
-            stdin_open: None,
+            ..Default::default()
         };
 
         let yaml = serde_yaml::to_string(&service_no_deps).unwrap();
@@ -371,6 +267,7 @@ mod regression_tests {
 
         let options = ProcessingOptions {
             include_sensitive: false,
+            ..Default::default()
         };
 
         // Default should exclude sensitive data
@@ -379,6 +276,7 @@ mod regression_tests {
         // Test with sensitive data included
         let options_with_sensitive = ProcessingOptions {
             include_sensitive: true,
+            ..Default::default()
         };
         assert!(options_with_sensitive.include_sensitive);
     }